@@ -0,0 +1,201 @@
+use crate::datablock::{self, Block};
+use crate::line::Line;
+
+// A term defined by another spec, which autolinks can resolve to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anchor {
+    pub text: String,
+    pub link_type: String,
+    pub spec: String,
+    pub url: String,
+    pub link_for: Vec<String>,
+}
+
+// Take the "<pre class=anchors>" blocks out of the source, and parse the anchors that they
+// declare. Each line is a list of "key: val" pairs separated by ";", and indented lines
+// inherit the pairs of the lines above them, e.g.
+//
+//     urlPrefix: https://dom.spec.whatwg.org/; spec: DOM
+//         type: interface; text: Event; url: interface-event
+pub fn extract_anchors(lines: Vec<Line>) -> (Vec<Anchor>, Vec<Line>) {
+    let mut anchors: Vec<Anchor> = Vec::new();
+    let lines = datablock::scan_blocks(lines, &["anchors"], |block| {
+        anchors.extend(parse_block(&block));
+        Vec::new()
+    });
+    (anchors, lines)
+}
+
+fn parse_block(block: &Block) -> Vec<Anchor> {
+    let mut anchors: Vec<Anchor> = Vec::new();
+    // (indentation, pairs) of the lines that the current line is nested in
+    let mut parents: Vec<(usize, Vec<(String, String)>)> = Vec::new();
+
+    for line in block.lines.iter() {
+        let location = Some(line.location());
+        let indent = line.text.len() - line.text.trim_start().len();
+        let mut pairs: Vec<(String, String)> = Vec::new();
+        for pair in line
+            .text
+            .split(';')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            match pair.find(':') {
+                Some(pos) => pairs.push((
+                    pair[..pos].trim().to_lowercase(),
+                    pair[pos + 1..].trim().to_owned(),
+                )),
+                None => die!(
+                    "Incorrectly formatted anchors line: \"{}\".",
+                    line.text.trim();
+                    location
+                ),
+            }
+        }
+
+        while parents.last().is_some_and(|(i, _)| *i >= indent) {
+            parents.pop();
+        }
+        let inherited: Vec<&(String, String)> =
+            parents.iter().flat_map(|(_, pairs)| pairs.iter()).collect();
+        let get = |key: &str| -> Option<String> {
+            pairs
+                .iter()
+                .chain(inherited.iter().cloned())
+                .find(|(k, _)| k == key)
+                .map(|(_, val)| val.clone())
+        };
+
+        let texts: Vec<&String> = pairs
+            .iter()
+            .filter(|(key, _)| key == "text")
+            .map(|(_, val)| val)
+            .collect();
+        if !texts.is_empty() {
+            let link_type = match get("type") {
+                Some(link_type) => link_type,
+                None => die!("Anchors need a \"type\"."; location),
+            };
+            let spec = match get("spec").or_else(|| get("shortname")) {
+                Some(spec) => spec,
+                None => die!("Anchors need a \"spec\"."; location),
+            };
+            // the url is appended to the prefixes of the lines above, unless it is absolute
+            let url = get("url").unwrap_or_default();
+            let url = if url.contains("://") {
+                url
+            } else {
+                inherited
+                    .iter()
+                    .cloned()
+                    .chain(pairs.iter())
+                    .filter(|(key, _)| key == "urlprefix")
+                    .map(|(_, val)| val.as_str())
+                    .collect::<String>()
+                    + &url
+            };
+            if url.is_empty() {
+                die!("Anchors need a \"url\" or a \"urlPrefix\"."; location);
+            }
+            let link_for: Vec<String> = pairs
+                .iter()
+                .chain(inherited.iter().cloned())
+                .filter(|(key, _)| key == "for")
+                .flat_map(|(_, val)| datablock::split_names(val))
+                .collect();
+
+            for text in texts {
+                anchors.push(Anchor {
+                    text: text.clone(),
+                    link_type: link_type.clone(),
+                    spec: spec.clone(),
+                    url: url.clone(),
+                    link_for: link_for.clone(),
+                });
+            }
+        }
+
+        parents.push((indent, pairs));
+    }
+
+    anchors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::Location;
+    use std::collections::BTreeMap;
+
+    fn new_block(texts: &[&str]) -> Block {
+        Block {
+            block_type: "anchors",
+            attrs: BTreeMap::new(),
+            lines: texts
+                .iter()
+                .map(|text| Line {
+                    text: text.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            location: Location::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_block() {
+        let anchors = parse_block(&new_block(&[
+            "urlPrefix: https://dom.spec.whatwg.org/; spec: DOM",
+            "    type: interface; text: Event; url: interface-event",
+            "    urlPrefix: #; type: method; for: Event",
+            "        text: stopPropagation(); url: dom-event-stoppropagation",
+            "type: dfn; spec: HTML; text: tree; text: trees; url: https://html.spec.whatwg.org/#tree",
+        ]));
+        assert_eq!(
+            anchors,
+            vec![
+                Anchor {
+                    text: "Event".to_owned(),
+                    link_type: "interface".to_owned(),
+                    spec: "DOM".to_owned(),
+                    url: "https://dom.spec.whatwg.org/interface-event".to_owned(),
+                    link_for: Vec::new(),
+                },
+                Anchor {
+                    text: "stopPropagation()".to_owned(),
+                    link_type: "method".to_owned(),
+                    spec: "DOM".to_owned(),
+                    url: "https://dom.spec.whatwg.org/#dom-event-stoppropagation".to_owned(),
+                    link_for: vec!["Event".to_owned()],
+                },
+                Anchor {
+                    text: "tree".to_owned(),
+                    link_type: "dfn".to_owned(),
+                    spec: "HTML".to_owned(),
+                    url: "https://html.spec.whatwg.org/#tree".to_owned(),
+                    link_for: Vec::new(),
+                },
+                Anchor {
+                    text: "trees".to_owned(),
+                    link_type: "dfn".to_owned(),
+                    spec: "HTML".to_owned(),
+                    url: "https://html.spec.whatwg.org/#tree".to_owned(),
+                    link_for: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_block_forgets_dedented_lines() {
+        let anchors = parse_block(&new_block(&[
+            "spec: A; urlPrefix: https://a.example/",
+            "    type: dfn; text: foo; url: #foo",
+            "spec: B; urlPrefix: https://b.example/",
+            "    type: dfn; text: bar; url: #bar",
+        ]));
+        assert_eq!(anchors[1].spec, "B");
+        assert_eq!(anchors[1].url, "https://b.example/#bar");
+    }
+}
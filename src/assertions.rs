@@ -0,0 +1,219 @@
+use kuchiki::iter::NodeIterator;
+use kuchiki::NodeRef;
+use std::collections::HashSet;
+use std::fs;
+
+use crate::boilerplate;
+use crate::html;
+use crate::issues_examples;
+use crate::spec::Spec;
+
+// Elements that hold whole sentences, so that the sentence of a keyword is found within them.
+const BLOCK_ELEMENTS: [&str; 10] = [
+    "p",
+    "li",
+    "dd",
+    "dt",
+    "td",
+    "th",
+    "div",
+    "blockquote",
+    "section",
+    "main",
+];
+
+// A testable assertion of the spec.
+#[derive(Debug, Clone)]
+pub struct Assertion {
+    pub id: String,
+    pub section: Option<String>,
+    pub text: String,
+}
+
+// Collect the testable assertions: the sentences of normative text with an RFC 2119 keyword,
+// and the text marked with "<span class=assertion>". Each assertion gets a stable id, which
+// tests can refer to.
+pub fn collect_assertions(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let main = match document.select_first("main") {
+        Ok(main) => main.as_node().clone(),
+        Err(_) => return,
+    };
+
+    let mut assertions: Vec<Assertion> = Vec::new();
+    let mut used_ids: HashSet<String> = HashSet::new();
+    // (block, start of the sentence) of the sentences that are already collected
+    let mut seen_sentences: Vec<(NodeRef, usize)> = Vec::new();
+    let mut heading: Option<NodeRef> = None;
+
+    for el in main.descendants().elements() {
+        let el = el.as_node();
+        if ["h2", "h3", "h4", "h5", "h6"]
+            .iter()
+            .any(|name| html::node::is_element(el, name))
+            && !html::node::has_class(el, "no-ref")
+        {
+            heading = Some(el.clone());
+            continue;
+        }
+        if !html::node::is_element(el, "span")
+            || el
+                .ancestors()
+                .any(|ancestor| html::node::get_attr(&ancestor, "data-fill-with").is_some())
+        {
+            continue;
+        }
+
+        let text = if html::node::has_class(el, "assertion") {
+            normalize_space(&el.text_contents())
+        } else if html::node::has_class(el, "rfc2119") {
+            // keywords in explicit assertions are part of them
+            if el
+                .ancestors()
+                .any(|ancestor| html::node::has_class(&ancestor, "assertion"))
+            {
+                continue;
+            }
+            let block = match el.ancestors().find(|ancestor| {
+                BLOCK_ELEMENTS
+                    .iter()
+                    .any(|name| html::node::is_element(ancestor, name))
+            }) {
+                Some(block) => block,
+                None => continue,
+            };
+            let block_text = block.text_contents();
+            let (start, end) = sentence_around(&block_text, text_offset(&block, el));
+            if seen_sentences
+                .iter()
+                .any(|(b, s)| *b == block && *s == start)
+            {
+                continue;
+            }
+            seen_sentences.push((block, start));
+            normalize_space(&block_text[start..end])
+        } else {
+            continue;
+        };
+
+        let id = issues_examples::ensure_hash_id(el, "assert", &text, &mut used_ids);
+        assertions.push(Assertion {
+            id,
+            section: heading.as_ref().map(boilerplate::section_name),
+            text,
+        });
+    }
+
+    doc.assertions = assertions;
+}
+
+// Write the assertions as a JSON manifest (e.g. "foo.assertions.json" for "foo.html").
+pub fn write_manifest(doc: &Spec, outfile: &str) {
+    if outfile == "-" {
+        warn!("The assertions manifest is only written next to an output file.");
+        return;
+    }
+    let manifest_path = format!(
+        "{}.assertions.json",
+        outfile.strip_suffix(".html").unwrap_or(outfile)
+    );
+
+    let base_url = doc.md.canonical_url.clone().unwrap_or_default();
+    let assertions: Vec<serde_json::Value> = doc
+        .assertions
+        .iter()
+        .map(|assertion| {
+            serde_json::json!({
+                "id": assertion.id,
+                "section": assertion.section,
+                "text": assertion.text,
+                "url": format!("{}#{}", base_url, assertion.id),
+            })
+        })
+        .collect();
+    let manifest = serde_json::json!({
+        "title": doc.md.title,
+        "url": doc.md.canonical_url,
+        "assertions": assertions,
+    });
+
+    let rendered = serde_json::to_string_pretty(&manifest).unwrap();
+    fs::write(manifest_path, rendered + "\n").expect("unable to write file");
+}
+
+// The length of the text of the block that comes before the element.
+fn text_offset(block: &NodeRef, el: &NodeRef) -> usize {
+    let mut offset = 0;
+    for node in block.descendants() {
+        if node == *el {
+            break;
+        }
+        if let Some(text) = node.as_text() {
+            offset += text.borrow().len();
+        }
+    }
+    offset
+}
+
+// Find the sentence of the text that contains the offset, as a (start, end) range.
+fn sentence_around(text: &str, offset: usize) -> (usize, usize) {
+    let is_sentence_end = |(i, c): &(usize, char)| {
+        matches!(c, '.' | '!' | '?')
+            && text[i + c.len_utf8()..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace)
+    };
+
+    let start = text[..offset]
+        .char_indices()
+        .rev()
+        .find(is_sentence_end)
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let end = text[offset..]
+        .char_indices()
+        .map(|(i, c)| (offset + i, c))
+        .find(is_sentence_end)
+        .map_or(text.len(), |(i, c)| i + c.len_utf8());
+    (start, end)
+}
+
+fn normalize_space(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+
+    #[test]
+    fn test_sentence_around() {
+        let text = "First one. UAs must do it 1.5 times! Last?";
+        let (start, end) = sentence_around(text, text.find("must").unwrap());
+        assert_eq!(&text[start..end], " UAs must do it 1.5 times!");
+        let (start, end) = sentence_around(text, 0);
+        assert_eq!(&text[start..end], "First one.");
+        let (start, end) = sentence_around(text, text.len() - 2);
+        assert_eq!(&text[start..end], " Last?");
+    }
+
+    #[test]
+    fn test_sentence_around_without_ends() {
+        let text = "no end here";
+        assert_eq!(sentence_around(text, 3), (0, text.len()));
+    }
+
+    #[test]
+    fn test_text_offset() {
+        let document = kuchiki::parse_html().one("<p>One <em>two</em> <span>three</span></p>");
+        let block = document.select_first("p").unwrap().as_node().clone();
+        let span = document.select_first("span").unwrap().as_node().clone();
+        assert_eq!(text_offset(&block, &span), "One two ".len());
+    }
+
+    #[test]
+    fn test_normalize_space() {
+        assert_eq!(normalize_space("  UAs\n  must\tdo it. "), "UAs must do it.");
+    }
+}
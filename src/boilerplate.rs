@@ -1,6 +1,14 @@
+use kuchiki::iter::NodeIterator;
+use kuchiki::NodeRef;
+use std::collections::BTreeMap;
 use std::fs;
+use std::path::Path;
 
+use crate::anchors::Anchor;
+use crate::config::UNOFFICIAL_STATUSES;
 use crate::html;
+use crate::link;
+use crate::metadata::editor::Editor;
 use crate::spec::Spec;
 
 pub fn add_header_footer(data: &mut String) {
@@ -12,13 +20,126 @@ pub fn add_header_footer(data: &mut String) {
 }
 
 pub fn add_bikeshed_boilerplate(doc: &mut Spec) {
+    // the containers of omitted sections are dropped, even if nothing was generated for them
+    let document = doc.document.as_ref().unwrap();
+    for container in select_all(document, "[data-fill-with]") {
+        let tag = html::node::get_attr(&container, "data-fill-with").unwrap_or_default();
+        if !doc.md.is_boilerplate_on(&tag) {
+            container.detach();
+        }
+    }
+
     for (key, val) in doc.extra_styles.iter() {
+        if !doc.md.is_boilerplate_on(&format!("style-{}", key)) {
+            continue;
+        }
         doc.head.as_ref().unwrap().append(html::node::new_style(
             format!("/* style-{} */\n{}", key, val).as_str(),
         ));
     }
 }
 
+pub fn add_logo(doc: &mut Spec) {
+    let container = match get_fill_container("logo", doc, false) {
+        Some(container) => container,
+        None => return,
+    };
+
+    // an explicit logo wins over the logo of the org
+    if let Some(ref logo) = doc.md.logo {
+        container.append(html::node::new_element(
+            "img",
+            btreemap! {
+                "alt" => "logo".to_owned(),
+                "src" => logo.clone(),
+            },
+        ));
+        return;
+    }
+    if let Some(text) = retrieve_boilerplate(doc, "logo") {
+        fill_with_boilerplate(&container, &text, doc);
+    }
+}
+
+// Show the banner of the "Warning" metadata. Unofficial drafts get a banner by default.
+pub fn add_warning(doc: &mut Spec) {
+    let name = match doc.md.warning {
+        Some(ref warning) => warning.boilerplate_name(),
+        None if doc
+            .md
+            .raw_status
+            .as_ref()
+            .is_some_and(|status| UNOFFICIAL_STATUSES.contains(status.as_str())) =>
+        {
+            "warning-unofficial"
+        }
+        None => return,
+    };
+
+    let container = match get_fill_container("warning", doc, false) {
+        Some(container) => container,
+        None => return,
+    };
+
+    if let Some(text) = retrieve_boilerplate(doc, name) {
+        fill_with_boilerplate(&container, &text, doc);
+        doc.extra_styles
+            .insert("warning", include_str!("style/warning.css"));
+    }
+}
+
+// Add the conformance section of the org (e.g. "boilerplate/w3c/conformance.include").
+pub fn add_conformance(doc: &mut Spec) {
+    let text = match retrieve_boilerplate(doc, "conformance") {
+        Some(text) => text,
+        None => return,
+    };
+    let container = match get_fill_container("conformance", doc, true) {
+        Some(container) => container,
+        None => return,
+    };
+    fill_with_boilerplate(&container, &text, doc);
+}
+
+pub fn add_copyright(doc: &mut Spec) {
+    let container = match get_fill_container("copyright", doc, false) {
+        Some(container) => container,
+        None => return,
+    };
+
+    if let Some(text) = retrieve_boilerplate(doc, "copyright") {
+        fill_with_boilerplate(&container, &text, doc);
+    }
+}
+
+// Read the boilerplate file with the given name. A file next to the spec (e.g.
+// "logo.include") overrides the file of the org (e.g. "boilerplate/w3c/logo.include"),
+// which overrides the generic one (e.g. "boilerplate/logo.include").
+fn retrieve_boilerplate(doc: &Spec, name: &str) -> Option<String> {
+    let filename = format!("{}.include", name);
+    let spec_dir = Path::new(doc.infile)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+
+    let mut paths = vec![spec_dir.join(&filename)];
+    if let Some(org) = doc.md.org() {
+        paths.push(Path::new("boilerplate").join(org).join(&filename));
+    }
+    paths.push(Path::new("boilerplate").join(&filename));
+
+    paths
+        .iter()
+        .find(|path| path.is_file())
+        .map(|path| fs::read_to_string(path).expect("Fail to open boilerplate file"))
+}
+
+fn fill_with_boilerplate(container: &NodeRef, text: &str, doc: &Spec) {
+    let text = html::helper::replace_macros(text, &doc.macros);
+    for node in html::node::parse_fragment(&text) {
+        container.append(node);
+    }
+}
+
 pub fn add_canonical_url(doc: &mut Spec) {
     if let Some(canonical_url) = &doc.md.canonical_url {
         doc.head.as_ref().unwrap().append(html::node::new_element(
@@ -30,3 +151,770 @@ pub fn add_canonical_url(doc: &mut Spec) {
         ))
     }
 }
+
+pub fn add_spec_metadata(doc: &mut Spec) {
+    let container = match get_fill_container("spec-metadata", doc, false) {
+        Some(container) => container,
+        None => return,
+    };
+
+    let md = &doc.md;
+    let mut entries: Vec<(&str, &str, Vec<NodeRef>)> = Vec::new();
+
+    if let Some(version) = md.version() {
+        let dd = html::node::new_element("dd", None);
+        dd.append(html::node::new_text_element(
+            "a",
+            btreemap! {
+                "class" => "u-url".to_owned(),
+                "href" => version.clone(),
+            },
+            &version,
+        ));
+        entries.push(("This version", "This version", vec![dd]));
+    }
+    if let Some(ref tr) = md.tr {
+        entries.push((
+            "Latest published version",
+            "Latest published version",
+            vec![new_link_dd(tr)],
+        ));
+    }
+    if let Some(ref implementation_report) = md.implementation_report {
+        entries.push((
+            "Implementation Report",
+            "Implementation Report",
+            vec![new_link_dd(implementation_report)],
+        ));
+    }
+    if let Some(ref test_suite) = md.test_suite {
+        entries.push(("Test Suite", "Test Suite", vec![new_link_dd(test_suite)]));
+    }
+    if let Some(ref deadline) = md.deadline {
+        let dd = html::node::new_element("dd", None);
+        dd.append(html::node::new_text_element(
+            "time",
+            btreemap! {
+                "datetime" => deadline.to_string(),
+            },
+            &deadline
+                .format(&format!("{} %B %Y", deadline.day()))
+                .to_string(),
+        ));
+        entries.push(("Deadline for feedback", "Deadline for feedback", vec![dd]));
+    }
+    if let Some(ref work_status) = md.work_status {
+        let dd = html::node::new_text_element("dd", None, work_status);
+        entries.push(("Work Status", "Work Status", vec![dd]));
+    }
+    let mut feedback: Vec<NodeRef> = Vec::new();
+    if let Some(ref mailing_list) = md.mailing_list {
+        feedback.push(new_mailing_list_dd(
+            mailing_list,
+            md.mailing_list_archives.as_deref(),
+            md.shortname.as_deref(),
+        ));
+    }
+    feedback.extend(md.feedback.iter().map(|val| {
+        if val.contains('@') && !val.contains("://") {
+            let dd = html::node::new_element("dd", None);
+            dd.append(html::node::new_text_element(
+                "a",
+                btreemap! {
+                    "href" => format!("mailto:{}", val.trim_start_matches("mailto:")),
+                },
+                val.trim_start_matches("mailto:"),
+            ));
+            dd
+        } else {
+            new_link_dd(val)
+        }
+    }));
+    if !feedback.is_empty() {
+        entries.push(("Feedback", "Feedback", feedback));
+    }
+    if !md.issue_trackers.is_empty() {
+        let dds = md
+            .issue_trackers
+            .iter()
+            .map(|(label, url)| {
+                let dd = html::node::new_element("dd", None);
+                dd.append(html::node::new_text_element(
+                    "a",
+                    btreemap! {
+                        "href" => url.clone(),
+                    },
+                    label,
+                ));
+                dd
+            })
+            .collect();
+        entries.push(("Issue Tracking", "Issue Tracking", dds));
+    }
+    if let Some(ref repository) = md.repository {
+        let dd = html::node::new_element("dd", None);
+        dd.append(html::node::new_text_element(
+            "a",
+            btreemap! {
+                "href" => repository.url.clone(),
+            },
+            &repository.name,
+        ));
+        entries.push(("Repository", "Repository", vec![dd]));
+    }
+    if !md.editors.is_empty() {
+        entries.push((
+            "Editor",
+            "Editors",
+            md.editors.iter().map(new_editor_dd).collect(),
+        ));
+    }
+    if !md.former_editors.is_empty() {
+        entries.push((
+            "Former Editor",
+            "Former Editors",
+            md.former_editors.iter().map(new_editor_dd).collect(),
+        ));
+    }
+
+    if entries.is_empty() {
+        return;
+    }
+
+    let dl = html::node::new_element("dl", None);
+    for (singular, plural, dds) in entries {
+        let key = if dds.len() > 1 { plural } else { singular };
+        let dt = html::node::new_text_element("dt", None, &format!("{}:", key));
+        if singular.ends_with("Editor") {
+            html::node::add_class(&dt, "editor");
+        }
+        dl.append(dt);
+        for dd in dds {
+            dl.append(dd);
+        }
+    }
+    container.append(dl);
+}
+
+fn new_link_dd(url: &str) -> NodeRef {
+    let dd = html::node::new_element("dd", None);
+    dd.append(html::node::new_text_element(
+        "a",
+        btreemap! {
+            "href" => url.to_owned(),
+        },
+        url,
+    ));
+    dd
+}
+
+// Render a mailing list with the subject line to use for the spec (e.g.
+// "www-style@w3.org with subject line “[css-foo] … message topic …” (archives)").
+fn new_mailing_list_dd(
+    mailing_list: &str,
+    archives: Option<&str>,
+    shortname: Option<&str>,
+) -> NodeRef {
+    let dd = html::node::new_element("dd", None);
+    let subject_prefix = shortname.map_or(String::new(), |shortname| format!("[{}] ", shortname));
+    dd.append(html::node::new_text_element(
+        "a",
+        btreemap! {
+            "href" => format!(
+                "mailto:{}?subject={}YOUR%20TOPIC%20HERE",
+                mailing_list,
+                subject_prefix
+                    .replace('[', "%5B")
+                    .replace(']', "%5D")
+                    .replace(' ', "%20")
+            ),
+        },
+        mailing_list,
+    ));
+    dd.append(NodeRef::new_text(" with subject line “"));
+    let kbd = html::node::new_element("kbd", None);
+    kbd.append(NodeRef::new_text(&subject_prefix));
+    kbd.append(html::node::new_text_element("i", None, "… message topic …"));
+    dd.append(kbd);
+    dd.append(NodeRef::new_text("”"));
+    if let Some(archives) = archives {
+        dd.append(NodeRef::new_text(" ("));
+        dd.append(html::node::new_text_element(
+            "a",
+            btreemap! {
+                "rel" => "discussion".to_owned(),
+                "href" => archives.to_owned(),
+            },
+            "archives",
+        ));
+        dd.append(NodeRef::new_text(")"));
+    }
+    dd
+}
+
+// Render an editor as an h-card (e.g. "<a class="p-name fn u-email email">Name</a>
+// (<a class="p-org org">Org</a>)").
+fn new_editor_dd(editor: &Editor) -> NodeRef {
+    let dd = html::node::new_element(
+        "dd",
+        btreemap! {
+            "class" => "editor p-author h-card vcard".to_owned(),
+        },
+    );
+    if let Some(ref w3c_id) = editor.w3c_id {
+        html::node::set_attr(&dd, "data-editor-id", w3c_id.clone());
+    }
+
+    // the name links to the editor's page, or else to their email
+    let name = match (&editor.link, &editor.email) {
+        (Some(link), _) => html::node::new_text_element(
+            "a",
+            btreemap! {
+                "class" => "p-name fn u-url url".to_owned(),
+                "href" => link.clone(),
+            },
+            &editor.name,
+        ),
+        (None, Some(email)) => html::node::new_text_element(
+            "a",
+            btreemap! {
+                "class" => "p-name fn u-email email".to_owned(),
+                "href" => format!("mailto:{}", email),
+            },
+            &editor.name,
+        ),
+        (None, None) => html::node::new_text_element(
+            "span",
+            btreemap! {
+                "class" => "p-name fn".to_owned(),
+            },
+            &editor.name,
+        ),
+    };
+    dd.append(name);
+
+    if let Some(ref org) = editor.org {
+        dd.append(NodeRef::new_text(" ("));
+        let org = match editor.org_link {
+            Some(ref org_link) => html::node::new_text_element(
+                "a",
+                btreemap! {
+                    "class" => "p-org org".to_owned(),
+                    "href" => org_link.clone(),
+                },
+                org,
+            ),
+            None => html::node::new_text_element(
+                "span",
+                btreemap! {
+                    "class" => "p-org org".to_owned(),
+                },
+                org,
+            ),
+        };
+        dd.append(org);
+        dd.append(NodeRef::new_text(")"));
+    }
+    if let (Some(_), Some(email)) = (&editor.link, &editor.email) {
+        dd.append(NodeRef::new_text(" "));
+        dd.append(html::node::new_text_element(
+            "a",
+            btreemap! {
+                "class" => "u-email email".to_owned(),
+                "href" => format!("mailto:{}", email),
+            },
+            email,
+        ));
+    }
+    if let Some(ref note) = editor.note {
+        dd.append(NodeRef::new_text(format!(" ({})", note)));
+    }
+
+    dd
+}
+
+// Find the container marked with "data-fill-with". A container that the author placed in
+// the main content wins over the one of the header or footer, which is then removed. If
+// there is none and the section has a default location, a new container is appended to
+// the body. Sections omitted by the "Boilerplate" metadata get no container.
+fn get_fill_container(tag: &str, doc: &Spec, default: bool) -> Option<NodeRef> {
+    if !doc.md.is_boilerplate_on(tag) {
+        return None;
+    }
+
+    let document = doc.document.as_ref().unwrap();
+    let selector = format!("[data-fill-with=\"{}\"]", tag);
+    let (authored, defaults): (Vec<NodeRef>, Vec<NodeRef>) = select_all(document, &selector)
+        .into_iter()
+        .partition(|container| {
+            container
+                .ancestors()
+                .any(|ancestor| html::node::is_element(&ancestor, "main"))
+        });
+    if let Some(container) = authored.first() {
+        for container in defaults.iter() {
+            container.detach();
+        }
+        return Some(container.clone());
+    }
+    if let Some(container) = defaults.first() {
+        return Some(container.clone());
+    }
+    if default {
+        let container = html::node::new_element(
+            "div",
+            btreemap! {
+                "data-fill-with" => tag.to_owned(),
+            },
+        );
+        doc.body.as_ref().unwrap().append(container.clone());
+        return Some(container);
+    }
+    None
+}
+
+pub fn add_property_index(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let prop_tables = select_all(document, "table.propdef:not(.partial)");
+    let desc_tables = select_all(document, "table.descdef:not(.partial)");
+
+    if prop_tables.is_empty() && desc_tables.is_empty() {
+        return;
+    }
+
+    let container = match get_fill_container("property-index", doc, true) {
+        Some(container) => container,
+        None => return,
+    };
+
+    container.append(html::node::new_text_element(
+        "h2",
+        btreemap! {
+            "class" => "no-num no-ref".to_owned(),
+            "id" => "property-index".to_owned(),
+        },
+        "Property Index",
+    ));
+
+    if prop_tables.is_empty() {
+        container.append(html::node::new_text_element(
+            "p",
+            None,
+            "No properties defined.",
+        ));
+    } else {
+        container.append(new_def_index_table(
+            &prop_tables,
+            "property",
+            &[
+                ("Name", "Name"),
+                ("Value", "Value"),
+                ("Initial", "Initial"),
+                ("Applies to", "Applies to"),
+                ("Inherited", "Inh."),
+                ("Percentages", "%ages"),
+                ("Animation type", "Anim\u{ad}ation type"),
+                ("Canonical order", "Canonical order"),
+                ("Computed value", "Com\u{ad}puted value"),
+            ],
+        ));
+    }
+
+    // group descriptors by their at-rules
+    let mut at_rules: BTreeMap<String, Vec<NodeRef>> = BTreeMap::new();
+    for table in desc_tables {
+        let at_rule = html::node::get_attr(&table, "data-dfn-for").unwrap_or_default();
+        at_rules.entry(at_rule).or_default().push(table);
+    }
+
+    for (at_rule, tables) in at_rules.iter() {
+        let heading = html::node::new_element(
+            "h3",
+            btreemap! {
+                "class" => "no-num no-ref".to_owned(),
+                "id" => format!("{}-descriptor-table", at_rule.trim_start_matches('@')),
+            },
+        );
+        heading.append(html::node::new_text_element(
+            "a",
+            btreemap! {
+                "class" => "css".to_owned(),
+                "data-link-type" => "at-rule".to_owned(),
+            },
+            at_rule,
+        ));
+        heading.append(NodeRef::new_text(" Descriptors"));
+        container.append(heading);
+        container.append(new_def_index_table(
+            tables,
+            "descriptor",
+            &[("Name", "Name"), ("Value", "Value"), ("Initial", "Initial")],
+        ));
+    }
+}
+
+pub fn add_element_index(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let tables = select_all(document, "table.elementdef");
+
+    if tables.is_empty() {
+        return;
+    }
+
+    let container = match get_fill_container("element-index", doc, true) {
+        Some(container) => container,
+        None => return,
+    };
+
+    container.append(html::node::new_text_element(
+        "h2",
+        btreemap! {
+            "class" => "no-num no-ref".to_owned(),
+            "id" => "element-index".to_owned(),
+        },
+        "Element Index",
+    ));
+    container.append(new_def_index_table(
+        &tables,
+        "element",
+        &[
+            ("Name", "Element"),
+            ("Categories", "Categories"),
+            ("Contexts", "Parents"),
+            ("Content model", "Children"),
+            ("Attributes", "Attributes"),
+            ("DOM Interfaces", "Interface"),
+        ],
+    ));
+}
+
+pub fn add_issues_index(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let issues = select_all(document, ".issue");
+
+    if issues.is_empty() {
+        return;
+    }
+
+    let container = match get_fill_container("issues-index", doc, true) {
+        Some(container) => container,
+        None => return,
+    };
+
+    container.append(html::node::new_text_element(
+        "h2",
+        btreemap! {
+            "class" => "no-num no-ref".to_owned(),
+            "id" => "issues-index".to_owned(),
+        },
+        "Issues Index",
+    ));
+
+    let list = html::node::new_element(
+        "div",
+        btreemap! {
+            "style" => "counter-reset:issue".to_owned(),
+        },
+    );
+    for issue in issues.iter() {
+        let el = if html::node::is_element(issue, "pre") {
+            html::node::new_element("pre", None)
+        } else {
+            html::node::new_element("div", None)
+        };
+        if let Some(class) = html::node::get_attr(issue, "class") {
+            html::node::set_attr(&el, "class", class);
+        }
+        for child in issue.children() {
+            el.append(html::node::deep_clone(&child));
+        }
+        el.append(NodeRef::new_text(" "));
+        el.append(html::node::new_text_element(
+            "a",
+            btreemap! {
+                "class" => "issue-return".to_owned(),
+                "href" => format!("#{}", html::node::get_attr(issue, "id").unwrap_or_default()),
+                "title" => "Jump to section".to_owned(),
+            },
+            "↵",
+        ));
+        list.append(el);
+    }
+
+    // the copies must not duplicate definitions or ids
+    dfns_to_links(&list);
+    for el in select_all(&list, "[id]") {
+        html::node::remove_attr(&el, "id");
+    }
+
+    container.append(list);
+}
+
+pub fn add_index_section(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+
+    // find the section of each definition
+    let mut entries: Vec<(String, String, NodeRef, Option<NodeRef>)> = Vec::new();
+    let mut heading: Option<NodeRef> = None;
+    for el in document.descendants().elements() {
+        let el = el.as_node();
+        if ["h2", "h3", "h4", "h5", "h6"]
+            .iter()
+            .any(|name| html::node::is_element(el, name))
+            && !html::node::has_class(el, "no-ref")
+        {
+            heading = Some(el.clone());
+        } else if html::node::is_element(el, "dfn") {
+            let text = link::dfn_text(el);
+            entries.push((text.to_lowercase(), text, el.clone(), heading.clone()));
+        }
+    }
+
+    if entries.is_empty() && doc.external_refs.is_empty() {
+        return;
+    }
+
+    let container = match get_fill_container("index", doc, true) {
+        Some(container) => container,
+        None => return,
+    };
+
+    container.append(html::node::new_text_element(
+        "h2",
+        btreemap! {
+            "class" => "no-num no-ref".to_owned(),
+            "id" => "index".to_owned(),
+        },
+        "Index",
+    ));
+    if !entries.is_empty() {
+        add_defined_here_index(&container, entries);
+    }
+    if !doc.external_refs.is_empty() {
+        add_defined_elsewhere_index(&container, &doc.external_refs);
+    }
+}
+
+// The index of the terms defined by this spec, as (sort key, text, dfn, heading of the
+// section) entries.
+fn add_defined_here_index(
+    container: &NodeRef,
+    mut entries: Vec<(String, String, NodeRef, Option<NodeRef>)>,
+) {
+    container.append(html::node::new_text_element(
+        "h3",
+        btreemap! {
+            "class" => "no-num no-ref".to_owned(),
+            "id" => "index-defined-here".to_owned(),
+        },
+        "Terms defined by this specification",
+    ));
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+    let list = html::node::new_element(
+        "ul",
+        btreemap! {
+            "class" => "index".to_owned(),
+        },
+    );
+    for (_, text, dfn, heading) in entries.iter() {
+        let li = html::node::new_element("li", None);
+        li.append(html::node::new_text_element(
+            "a",
+            btreemap! {
+                "href" => format!("#{}", html::node::get_attr(dfn, "id").unwrap_or_default()),
+            },
+            text,
+        ));
+
+        let mut description = match html::node::get_attr(dfn, "data-dfn-for") {
+            Some(dfn_for) => format!(", {} for {}", link::dfn_type(dfn), dfn_for),
+            None => format!(", {}", link::dfn_type(dfn)),
+        };
+        if let Some(heading) = heading {
+            description.push_str(&format!(", in {}", section_name(heading)));
+        }
+        li.append(html::node::new_text_element("span", None, &description));
+        list.append(li);
+    }
+    container.append(list);
+}
+
+// The index of the terms of other specs that this spec links to, grouped by spec.
+fn add_defined_elsewhere_index(container: &NodeRef, external_refs: &[Anchor]) {
+    container.append(html::node::new_text_element(
+        "h3",
+        btreemap! {
+            "class" => "no-num no-ref".to_owned(),
+            "id" => "index-defined-elsewhere".to_owned(),
+        },
+        "Terms defined by reference",
+    ));
+
+    let mut specs: BTreeMap<String, Vec<&Anchor>> = BTreeMap::new();
+    for anchor in external_refs.iter() {
+        specs
+            .entry(anchor.spec.to_uppercase())
+            .or_default()
+            .push(anchor);
+    }
+
+    let list = html::node::new_element(
+        "ul",
+        btreemap! {
+            "class" => "index".to_owned(),
+        },
+    );
+    for (spec, anchors) in specs.iter_mut() {
+        anchors.sort_by_key(|anchor| anchor.text.to_lowercase());
+        let li = html::node::new_element("li", None);
+        li.append(NodeRef::new_text(format!(
+            "[{}] defines the following terms:",
+            spec
+        )));
+        let terms = html::node::new_element("ul", None);
+        for anchor in anchors.iter() {
+            let term = html::node::new_element("li", None);
+            term.append(html::node::new_text_element(
+                "a",
+                btreemap! {
+                    "href" => anchor.url.clone(),
+                },
+                &anchor.text,
+            ));
+            let description = match anchor.link_for.first() {
+                Some(link_for) => format!(", {} for {}", anchor.link_type, link_for),
+                None => format!(", {}", anchor.link_type),
+            };
+            term.append(html::node::new_text_element("span", None, &description));
+            terms.append(term);
+        }
+        li.append(terms);
+        list.append(li);
+    }
+    container.append(list);
+}
+
+// Name a section by its number if it has one (e.g. "§ 2.1"), or else by its title.
+pub fn section_name(heading: &NodeRef) -> String {
+    match heading.select_first(".secno") {
+        Ok(secno) => format!("§ {}", secno.text_contents().trim()),
+        Err(_) => format!("§ {}", heading.text_contents().trim()),
+    }
+}
+
+fn select_all(node: &NodeRef, selectors: &str) -> Vec<NodeRef> {
+    node.select(selectors)
+        .unwrap()
+        .map(|el| el.as_node().clone())
+        .collect()
+}
+
+// Build an index table with one row per definition of the given type. The first column
+// links to the definition, and the others copy the matching rows of its definition table.
+fn new_def_index_table(tables: &[NodeRef], dfn_type: &str, columns: &[(&str, &str)]) -> NodeRef {
+    let thead_tr = html::node::new_element("tr", None);
+    for (_, heading) in columns.iter() {
+        thead_tr.append(html::node::new_text_element(
+            "th",
+            btreemap! {
+                "scope" => "col".to_owned(),
+            },
+            heading,
+        ));
+    }
+    let thead = html::node::new_element("thead", None);
+    thead.append(thead_tr);
+
+    let tbody = html::node::new_element("tbody", None);
+    for table in tables.iter() {
+        let rows = extract_def_rows(table);
+        let dfns = select_all(table, &format!("dfn[data-dfn-type={}]", dfn_type));
+
+        for dfn in dfns.iter() {
+            let tr = html::node::new_element("tr", None);
+            let th = html::node::new_element(
+                "th",
+                btreemap! {
+                    "scope" => "row".to_owned(),
+                },
+            );
+            th.append(new_self_ref(dfn, dfn_type));
+            tr.append(th);
+
+            for (key, _) in columns.iter().skip(1) {
+                let td = html::node::new_element("td", None);
+                if let Some((_, cell)) = rows.iter().find(|(k, _)| k == key) {
+                    for child in cell.children() {
+                        td.append(html::node::deep_clone(&child));
+                    }
+                    dfns_to_links(&td);
+                }
+                tr.append(td);
+            }
+
+            tbody.append(tr);
+        }
+    }
+
+    let index_table = html::node::new_element(
+        "table",
+        btreemap! {
+            "class" => "index".to_owned(),
+        },
+    );
+    index_table.append(thead);
+    index_table.append(tbody);
+
+    let wrapper = html::node::new_element(
+        "div",
+        btreemap! {
+            "class" => "big-element-wrapper".to_owned(),
+        },
+    );
+    wrapper.append(index_table);
+    wrapper
+}
+
+// Collect the "Key: val" rows of a definition table, with the trailing colon of each key
+// removed.
+fn extract_def_rows(table: &NodeRef) -> Vec<(String, NodeRef)> {
+    table
+        .select("tr")
+        .unwrap()
+        .filter_map(|tr| {
+            let th = tr.as_node().select_first("th").ok()?;
+            let td = tr.as_node().select_first("td").ok()?;
+            let key = th.text_contents().trim().trim_end_matches(':').to_owned();
+            Some((key, td.as_node().clone()))
+        })
+        .collect()
+}
+
+// Replace the definitions in copied content with links to the originals, so that
+// definitions and their ids stay unique.
+fn dfns_to_links(node: &NodeRef) {
+    for dfn in select_all(node, "dfn") {
+        let link_type = link::dfn_type(&dfn);
+        let link = new_self_ref(&dfn, &link_type);
+        if let Some(link_for) = html::node::get_attr(&dfn, "data-dfn-for") {
+            html::node::set_attr(&link, "data-link-for", link_for);
+        }
+        dfn.insert_before(link);
+        dfn.detach();
+    }
+}
+
+// Create a link that points to the given definition.
+fn new_self_ref(dfn: &NodeRef, link_type: &str) -> NodeRef {
+    let href = html::node::get_attr(dfn, "id").map_or(String::new(), |id| format!("#{}", id));
+    let mut attributes = btreemap! {
+        "data-link-type" => link_type.to_owned(),
+        "href" => href,
+    };
+    if link_type == "property" || link_type == "descriptor" {
+        attributes.insert("class", "css".to_owned());
+    }
+    html::node::new_text_element("a", attributes, dfn.text_contents().trim())
+}
@@ -1,5 +1,6 @@
 use clap::{App, Arg, ArgMatches, SubCommand};
 
+use crate::issues_list;
 use crate::metadata::metadata::Metadata;
 use crate::spec::Spec;
 
@@ -14,13 +15,44 @@ fn handle_spec(matches: ArgMatches) {
         .unwrap()
         .value_of("outfile");
 
-    let md_cli = Metadata::new();
+    let mut md_cli = Metadata::new();
+    if let Some(text_macros) = matches
+        .subcommand_matches("spec")
+        .unwrap()
+        .values_of("md-text-macro")
+    {
+        for text_macro in text_macros {
+            md_cli.add_data("Text Macro", text_macro, None);
+        }
+    }
 
     let mut doc = Spec::new(infile, md_cli);
+    doc.write_assertions = matches
+        .subcommand_matches("spec")
+        .unwrap()
+        .is_present("assertions");
+    doc.export_idl_json = matches
+        .subcommand_matches("spec")
+        .unwrap()
+        .is_present("export-idl-json");
     doc.preprocess();
     doc.finish(outfile);
 }
 
+fn handle_issues_list(matches: ArgMatches) {
+    let infile = matches
+        .subcommand_matches("issues-list")
+        .unwrap()
+        .value_of("infile")
+        .unwrap();
+    let outfile = matches
+        .subcommand_matches("issues-list")
+        .unwrap()
+        .value_of("outfile");
+
+    issues_list::print_issues_list(infile, outfile);
+}
+
 pub fn run() {
     let spec_subcommand = SubCommand::with_name("spec")
         .about("Process a spec source file into a valid output file")
@@ -31,6 +63,42 @@ pub fn run() {
                 .help("path to the source file")
                 .index(1),
         )
+        .arg(
+            Arg::with_name("outfile")
+                .takes_value(true)
+                .help("path to the output file")
+                .index(2),
+        )
+        .arg(
+            Arg::with_name("md-text-macro")
+                .long("md-text-macro")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("define a text macro, like \"FOO some text\""),
+        )
+        .arg(
+            Arg::with_name("assertions")
+                .long("assertions")
+                .help("write the testable assertions to a JSON manifest next to the output file"),
+        )
+        .arg(
+            Arg::with_name("export-idl-json")
+                .long("export-idl-json")
+                .help(
+                    "write the parsed IDL as a webidl2.js-style JSON AST next to the output file",
+                ),
+        );
+
+    let issues_list_subcommand = SubCommand::with_name("issues-list")
+        .about("Process a plain-text issues file into HTML")
+        .arg(
+            Arg::with_name("infile")
+                .required(true)
+                .takes_value(true)
+                .help("path to the plain-text issues file")
+                .index(1),
+        )
         .arg(
             Arg::with_name("outfile")
                 .takes_value(true)
@@ -42,10 +110,12 @@ pub fn run() {
         .version("1.0")
         .author("whichxjy")
         .subcommand(spec_subcommand)
+        .subcommand(issues_list_subcommand)
         .get_matches();
 
     match matches.subcommand_name() {
         Some("spec") => handle_spec(matches),
+        Some("issues-list") => handle_issues_list(matches),
         _ => {}
     }
 }
@@ -4,6 +4,37 @@ lazy_static! {
     pub static ref SOURCE_FILE_EXTENSIONS: HashSet<&'static str> = {
         hashset! {".bs", ".src.html"}
     };
+    // group => org, for groups whose specs use plain statuses
+    pub static ref GROUP_TO_ORG: HashMap<&'static str, &'static str> = {
+        hashmap! {
+            "csswg" => "w3c",
+            "fxtf" => "w3c",
+            "houdini" => "w3c",
+            "svg" => "w3c",
+            "webappsec" => "w3c",
+            "webapps" => "w3c",
+            "webperf" => "w3c",
+            "webplatform" => "w3c",
+            "wicg" => "w3c",
+            "i18n" => "w3c",
+            "whatwg" => "whatwg",
+            "tc39" => "tc39",
+            "wg21" => "iso",
+            "fido" => "fido",
+            "khronos" => "khronos"
+        }
+    };
+    // statuses of drafts that are not official documents of their org
+    pub static ref UNOFFICIAL_STATUSES: HashSet<&'static str> = {
+        hashset! {"w3c/UD", "DREAM"}
+    };
+    // statuses of snapshots published on /TR/
+    pub static ref SNAPSHOT_STATUSES: HashSet<&'static str> = {
+        hashset! {
+            "w3c/WD", "w3c/FPWD", "w3c/LCWD", "w3c/CR", "w3c/PR", "w3c/REC", "w3c/PER",
+            "w3c/WG-NOTE", "w3c/IG-NOTE", "w3c/NOTE", "w3c/MO"
+        }
+    };
     pub static ref SHORT_TO_LONG_STATUS: HashMap<&'static str, &'static str> = {
         hashmap! {
             "DREAM" => "A Collection of Interesting Ideas",
@@ -0,0 +1,200 @@
+use regex::Regex;
+
+use super::{parse_pairs, Block};
+use crate::html;
+use crate::idl::{self, Argument, Definition};
+use crate::spec::Spec;
+
+// What the type columns show for an argument that isn't in the IDL.
+const UNKNOWN: &str = "—";
+
+pub fn transform_argumentdef(block: &Block, doc: &Spec) -> Vec<String> {
+    lazy_static! {
+        // method signature reg (e.g. "Foo/bar(a, b)")
+        static ref SIGNATURE_REG: Regex = Regex::new(r"^(?:(.+)/)?([^/(]+)\((.*)\)$").unwrap();
+    }
+
+    let for_val = match block.attrs.get("for") {
+        Some(for_val) => for_val.trim().to_owned(),
+        None => {
+            die!("Argumentdef blocks need a for='' attribute specifying their method."; Some(&block.location))
+        }
+    };
+    let caps = match SIGNATURE_REG.captures(&for_val) {
+        Some(caps) => caps,
+        None => die!(
+            "The for='{}' of the argumentdef block is not a method signature.",
+            for_val;
+            Some(&block.location)
+        ),
+    };
+    let interface = caps.get(1).map_or("", |m| m.as_str());
+    let method = &caps[2];
+    let signature_args: Vec<&str> = caps[3]
+        .split(',')
+        .map(|arg| arg.trim().trim_end_matches("...").trim())
+        .filter(|arg| !arg.is_empty())
+        .collect();
+
+    let args = parse_pairs(block);
+
+    // cross-check the documented arguments against the method signature
+    for (name, _) in args.iter() {
+        if !signature_args.contains(&name.as_str()) {
+            warn!(
+                "The argumentdef for {} describes an argument \"{}\" that is not in its signature.",
+                for_val,
+                name;
+                Some(&block.location)
+            );
+        }
+    }
+    for name in signature_args.iter() {
+        if args.iter().all(|(arg, _)| arg != name) {
+            warn!(
+                "The argumentdef for {} is missing the argument \"{}\".",
+                for_val,
+                name;
+                Some(&block.location)
+            );
+        }
+    }
+
+    // the IDL gives the types, if it defines the interface
+    let overload = if idl::has_container(&doc.idl, interface) {
+        find_overload(
+            block,
+            &doc.idl,
+            &for_val,
+            interface,
+            method,
+            &signature_args,
+        )
+    } else {
+        None
+    };
+
+    let mut new_lines: Vec<String> = Vec::new();
+    new_lines.push(format!(
+        "<table class=\"data argumentdef\" data-dfn-for=\"{}\">",
+        for_val
+    ));
+    new_lines.push(format!(
+        "<caption>Arguments for the <a data-link-type=\"method\" data-link-for=\"{}\">{}{}({})</a> method.</caption>",
+        interface,
+        if interface.is_empty() { String::new() } else { format!("{}.", interface) },
+        method,
+        signature_args.join(", ")
+    ));
+    new_lines.push(String::from(
+        "<thead><tr><th>Parameter<th>Type<th>Nullable<th>Optional<th>Description</thead>",
+    ));
+    new_lines.push(String::from("<tbody>"));
+    for (name, description) in args.iter() {
+        let argument = overload.and_then(|arguments| arguments.iter().find(|a| a.name == *name));
+        let (idl_type, nullable, optional) = match argument {
+            Some(argument) => (
+                type_cell(argument),
+                check_mark(argument.idl_type.nullable),
+                check_mark(argument.optional),
+            ),
+            None => (String::from(UNKNOWN), UNKNOWN, UNKNOWN),
+        };
+        new_lines.push(format!(
+            "<tr><td><dfn data-dfn-type=\"argument\" data-dfn-for=\"{}\" data-export id=\"{}\">{}</dfn><td>{}<td>{}<td>{}<td>{}",
+            for_val,
+            argument_id(&for_val, name),
+            name,
+            idl_type,
+            nullable,
+            optional,
+            description
+        ));
+    }
+    new_lines.push(String::from("</table>"));
+    new_lines
+}
+
+// Find the overload of the method in the IDL whose arguments are the ones of the signature.
+// A mismatch is reported.
+fn find_overload<'a>(
+    block: &Block,
+    definitions: &'a [Definition],
+    for_val: &str,
+    interface: &str,
+    method: &str,
+    signature_args: &[&str],
+) -> Option<&'a [Argument]> {
+    let overloads = idl::find_overloads(definitions, interface, method);
+    let overload = overloads.iter().cloned().find(|arguments| {
+        arguments.len() == signature_args.len()
+            && arguments
+                .iter()
+                .zip(signature_args.iter())
+                .all(|(argument, name)| argument.name == *name)
+    });
+
+    if overloads.is_empty() {
+        warn!(
+            "The argumentdef for {} describes a method that is not in the IDL.",
+            for_val;
+            Some(&block.location)
+        );
+    } else if overload.is_none() {
+        let idl_signatures = overloads
+            .iter()
+            .map(|arguments| {
+                let names = arguments
+                    .iter()
+                    .map(|argument| argument.name.as_str())
+                    .collect::<Vec<&str>>();
+                format!("{}({})", method, names.join(", "))
+            })
+            .collect::<Vec<String>>();
+        warn!(
+            "The argumentdef for {} doesn't match the arguments of the method in the IDL: {}.",
+            for_val,
+            idl_signatures.join(", ");
+            Some(&block.location)
+        );
+    }
+
+    overload
+}
+
+// Generate an id like "dom-foo-bar-a-b-a" for the argument "a" of "Foo/bar(a, b)".
+fn argument_id(for_val: &str, name: &str) -> String {
+    format!("dom-{}-{}", for_val, name)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+// The type of the argument, whose nullability has its own column.
+fn type_cell(argument: &Argument) -> String {
+    let mut idl_type = argument.idl_type.clone();
+    idl_type.nullable = false;
+    let variadic = if argument.variadic { "..." } else { "" };
+    html::helper::escape_html(&format!("{}{}", idl_type, variadic))
+}
+
+fn check_mark(val: bool) -> &'static str {
+    if val {
+        "✔"
+    } else {
+        "✘"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argument_id() {
+        assert_eq!(argument_id("Foo/bar(a, b)", "a"), "dom-foo-bar-a-b-a");
+        assert_eq!(argument_id("bar(...rest)", "rest"), "dom-bar-rest-rest");
+    }
+}
@@ -0,0 +1,178 @@
+use super::propdef::link_value_grammar;
+use super::{get_val, parse_def_block, split_names, Block};
+use crate::spec::Spec;
+
+pub fn transform_descdef(block: &Block, _doc: &Spec) -> Vec<String> {
+    let pairs = parse_def_block(block);
+    let names = get_val(&pairs, "Name").map_or(Vec::new(), split_names);
+    let is_partial = block.has_class("partial") || get_val(&pairs, "New values").is_some();
+
+    let at_rule = match get_val(&pairs, "For") {
+        Some(at_rule) => at_rule.trim().to_owned(),
+        None => die!(
+            "The descdef for \"{}\" is missing a \"For\" line.",
+            names.join(", ");
+            Some(&block.location)
+        ),
+    };
+
+    // Entries with a default value are optional, and the others are required.
+    let keys: Vec<(&str, Option<&str>)> = if is_partial {
+        vec![("Name", None), ("For", None), ("New values", None)]
+    } else {
+        vec![
+            ("Name", None),
+            ("For", None),
+            ("Value", None),
+            ("Initial", None),
+        ]
+    };
+
+    let mut new_lines: Vec<String> = Vec::new();
+    new_lines.push(format!(
+        "<table class=\"def descdef{}\" data-dfn-for=\"{}\">",
+        if is_partial { " partial" } else { "" },
+        at_rule,
+    ));
+    new_lines.push(String::from("<tbody>"));
+
+    for (key, default) in keys.iter() {
+        let val = match get_val(&pairs, key).or(*default) {
+            Some(val) => val,
+            None => die!(
+                "The descdef for \"{}\" is missing a \"{}\" line.",
+                names.join(", "),
+                key;
+                Some(&block.location)
+            ),
+        };
+        new_lines.push(match *key {
+            "Name" if is_partial => format!(
+                "<tr><th>Name:<td>{}",
+                names
+                    .iter()
+                    .map(|name| format!(
+                        "<a class=\"descriptor\" data-link-type=\"descriptor\" data-link-for=\"{}\">{}</a>",
+                        at_rule, name
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            "Name" => format!(
+                "<tr><th>Name:<td>{}",
+                names
+                    .iter()
+                    .map(|name| format!(
+                        "<dfn class=\"css\" data-dfn-type=\"descriptor\" data-dfn-for=\"{0}\" data-export id=\"descdef-{1}-{2}\">{2}</dfn>",
+                        at_rule,
+                        at_rule.trim_start_matches('@'),
+                        name
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            "For" => format!(
+                "<tr><th>For:<td><a class=\"css\" data-link-type=\"at-rule\">{}</a>",
+                at_rule
+            ),
+            "Value" | "New values" => format!(
+                "<tr class=\"value\"><th>{}:<td class=\"prod\">{}",
+                key,
+                link_value_grammar(
+                    val,
+                    &format!("{}/{}", at_rule, names.first().map_or("", |name| name.as_str()))
+                )
+            ),
+            _ => format!("<tr><th>{}:<td>{}", key, val),
+        });
+    }
+
+    // display unknown entries in the order they were specified
+    for (key, val) in pairs.iter() {
+        if keys.iter().all(|(k, _)| k != key) {
+            new_lines.push(format!("<tr><th>{}:<td>{}", key, val));
+        }
+    }
+
+    new_lines.push(String::from("</table>"));
+    new_lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::{Line, Location};
+    use std::collections::BTreeMap;
+
+    fn new_block(class: Option<&str>, texts: &[&str]) -> Block {
+        let mut attrs = BTreeMap::new();
+        if let Some(class) = class {
+            attrs.insert("class".to_owned(), class.to_owned());
+        }
+        Block {
+            block_type: "descdef",
+            attrs,
+            lines: texts
+                .iter()
+                .enumerate()
+                .map(|(i, text)| Line {
+                    index: i as u32 + 2,
+                    text: text.to_string(),
+                    file: None,
+                })
+                .collect(),
+            location: Location::default(),
+        }
+    }
+
+    #[test]
+    fn test_transform_descdef() {
+        let block = new_block(
+            None,
+            &[
+                "Name: font-display",
+                "For: @font-face",
+                "Value: auto",
+                "Initial: auto",
+            ],
+        );
+        let lines = transform_descdef(&block, &Spec::default());
+        assert_eq!(
+            lines[0],
+            "<table class=\"def descdef\" data-dfn-for=\"@font-face\">"
+        );
+        assert_eq!(
+            lines[2],
+            "<tr><th>Name:<td><dfn class=\"css\" data-dfn-type=\"descriptor\" data-dfn-for=\"@font-face\" data-export id=\"descdef-font-face-font-display\">font-display</dfn>"
+        );
+        assert_eq!(
+            lines[3],
+            "<tr><th>For:<td><a class=\"css\" data-link-type=\"at-rule\">@font-face</a>"
+        );
+        assert!(lines[4].starts_with("<tr class=\"value\"><th>Value:<td class=\"prod\">"));
+        assert_eq!(lines[5], "<tr><th>Initial:<td>auto");
+        assert_eq!(lines.len(), 7);
+    }
+
+    #[test]
+    fn test_transform_partial_descdef() {
+        let block = new_block(
+            Some("partial"),
+            &[
+                "Name: font-display",
+                "For: @font-face",
+                "New values: fallback",
+            ],
+        );
+        let lines = transform_descdef(&block, &Spec::default());
+        assert_eq!(
+            lines[0],
+            "<table class=\"def descdef partial\" data-dfn-for=\"@font-face\">"
+        );
+        assert_eq!(
+            lines[2],
+            "<tr><th>Name:<td><a class=\"descriptor\" data-link-type=\"descriptor\" data-link-for=\"@font-face\">font-display</a>"
+        );
+        assert_eq!(lines.len(), 6);
+    }
+}
@@ -0,0 +1,164 @@
+use super::{get_val, parse_def_block, split_names, Block};
+use crate::spec::Spec;
+
+pub fn transform_elementdef(block: &Block, _doc: &Spec) -> Vec<String> {
+    let pairs = parse_def_block(block);
+    let names = get_val(&pairs, "Name").map_or(Vec::new(), split_names);
+
+    let keys = [
+        "Name",
+        "Categories",
+        "Contexts",
+        "Content model",
+        "Attributes",
+        "Dom interfaces",
+    ];
+
+    let mut new_lines: Vec<String> = Vec::new();
+    new_lines.push(String::from("<table class=\"def elementdef\">"));
+    new_lines.push(String::from("<tbody>"));
+
+    for key in keys.iter() {
+        let val = match get_val(&pairs, key) {
+            Some(val) => val,
+            None => die!(
+                "The elementdef for \"{}\" is missing a \"{}\" line.",
+                names.join(", "),
+                key;
+                Some(&block.location)
+            ),
+        };
+        new_lines.push(match *key {
+            "Name" => format!(
+                "<tr><th>Name:<td>{}",
+                names
+                    .iter()
+                    .map(|name| format!(
+                        "<dfn data-dfn-type=\"element\" data-export id=\"elementdef-{0}\">{0}</dfn>",
+                        name
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            "Attributes" => format!(
+                "<tr><th>Attributes:<td><ul>{}</ul>",
+                split_names(val)
+                    .iter()
+                    .map(|attr| format!("<li>{}", render_attribute(attr, &names)))
+                    .collect::<String>()
+            ),
+            "Dom interfaces" => format!(
+                "<tr><th>DOM Interfaces:<td><ul>{}</ul>",
+                split_names(val)
+                    .iter()
+                    .map(|interface| format!(
+                        "<li><a data-link-type=\"interface\">{}</a>",
+                        interface
+                    ))
+                    .collect::<String>()
+            ),
+            _ => format!(
+                "<tr><th>{}:<td><ul>{}</ul>",
+                key,
+                split_names(val)
+                    .iter()
+                    .map(|item| format!("<li>{}", item))
+                    .collect::<String>()
+            ),
+        });
+    }
+
+    // display unknown entries in the order they were specified
+    for (key, val) in pairs.iter() {
+        if keys.iter().all(|k| k != key) {
+            new_lines.push(format!("<tr><th>{}:<td>{}", key, val));
+        }
+    }
+
+    new_lines.push(String::from("</table>"));
+    new_lines
+}
+
+// A bare attribute name defines an attribute of the element(s), while anything else (markup
+// or an attribute group like "global attributes") is displayed as-is.
+fn render_attribute(attr: &str, names: &[String]) -> String {
+    if attr.contains('<') || attr.contains(char::is_whitespace) {
+        return attr.to_owned();
+    }
+    let element = names.first().map_or("", |name| name.as_str());
+    format!(
+        "<dfn data-dfn-type=\"element-attr\" data-dfn-for=\"{}\" data-export id=\"element-attrdef-{}-{}\">{2}</dfn>",
+        names.join(", "),
+        element,
+        attr
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::{Line, Location};
+    use std::collections::BTreeMap;
+
+    fn new_block(texts: &[&str]) -> Block {
+        Block {
+            block_type: "elementdef",
+            attrs: BTreeMap::new(),
+            lines: texts
+                .iter()
+                .enumerate()
+                .map(|(i, text)| Line {
+                    index: i as u32 + 2,
+                    text: text.to_string(),
+                    file: None,
+                })
+                .collect(),
+            location: Location::default(),
+        }
+    }
+
+    #[test]
+    fn test_transform_elementdef() {
+        let block = new_block(&[
+            "Name: foo, bar",
+            "Categories: Flow content",
+            "Contexts: Where flow content is expected",
+            "Content model: Nothing",
+            "Attributes: href, <a>global attributes</a>",
+            "Dom interfaces: HTMLFooElement",
+            "Extra: baz",
+        ]);
+        let lines = transform_elementdef(&block, &Spec::default());
+        assert_eq!(lines.first().unwrap(), "<table class=\"def elementdef\">");
+        assert_eq!(
+            lines[2],
+            "<tr><th>Name:<td>\
+             <dfn data-dfn-type=\"element\" data-export id=\"elementdef-foo\">foo</dfn>, \
+             <dfn data-dfn-type=\"element\" data-export id=\"elementdef-bar\">bar</dfn>"
+        );
+        assert_eq!(lines[3], "<tr><th>Categories:<td><ul><li>Flow content</ul>");
+        assert_eq!(
+            lines[7],
+            "<tr><th>DOM Interfaces:<td><ul><li><a data-link-type=\"interface\">HTMLFooElement</a></ul>"
+        );
+        assert_eq!(lines[8], "<tr><th>Extra:<td>baz");
+        assert_eq!(lines.last().unwrap(), "</table>");
+    }
+
+    #[test]
+    fn test_render_attribute() {
+        let names = vec!["foo".to_owned(), "bar".to_owned()];
+        assert_eq!(
+            render_attribute("href", &names),
+            "<dfn data-dfn-type=\"element-attr\" data-dfn-for=\"foo, bar\" data-export id=\"element-attrdef-foo-href\">href</dfn>"
+        );
+        assert_eq!(
+            render_attribute("global attributes", &names),
+            "global attributes"
+        );
+        assert_eq!(
+            render_attribute("<a>global attributes</a>", &names),
+            "<a>global attributes</a>"
+        );
+    }
+}
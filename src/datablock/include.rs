@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use super::{parse_pairs, scan_blocks, Block};
+use crate::html;
+use crate::line::Line;
+use crate::spec::Spec;
+use crate::util::reader;
+
+// Replace the "<pre class=include>" blocks with the lines of the files they include. The
+// included files can include other files, and their paths are relative to the file that
+// includes them.
+pub fn transform_includes(lines: Vec<Line>, infile: &str) -> Vec<Line> {
+    let infile = Path::new(infile);
+    let stack = vec![infile
+        .canonicalize()
+        .unwrap_or_else(|_| infile.to_path_buf())];
+    transform_includes_in_file(lines, infile, &stack)
+}
+
+fn transform_includes_in_file(lines: Vec<Line>, file: &Path, stack: &[PathBuf]) -> Vec<Line> {
+    scan_blocks(lines, &["include"], |block| {
+        include_file(&block, file, stack)
+    })
+}
+
+fn include_file(block: &Block, including_file: &Path, stack: &[PathBuf]) -> Vec<Line> {
+    let mut path = block.attrs.get("path").cloned();
+    let mut macros: HashMap<String, String> = HashMap::new();
+
+    for (key, val) in parse_pairs(block) {
+        let key = key.to_lowercase();
+        if key == "path" {
+            path = Some(val);
+        } else if let Some(name) = key.strip_prefix("macro-") {
+            macros.insert(name.to_owned(), val);
+        } else {
+            die!("Unknown include parameter \"{}\".", key; Some(&block.location));
+        }
+    }
+
+    let path = match path {
+        Some(path) => resolve_path(&path, including_file),
+        None => die!("Include blocks need a path."; Some(&block.location)),
+    };
+
+    let canonical_path = path.canonicalize().unwrap_or_else(|_| path.clone());
+    if let Some(cycle) = find_include_cycle(stack, &canonical_path) {
+        die!("Include cycle detected: {}", cycle; Some(&block.location));
+    }
+
+    let src_lines = match reader::read_lines(&path) {
+        Ok(src_lines) => src_lines,
+        Err(_) => die!(
+            "Couldn't find the include file \"{}\".",
+            path.display();
+            Some(&block.location)
+        ),
+    };
+
+    let file: Rc<str> = Rc::from(path.display().to_string());
+    let mut lines: Vec<Line> = Vec::new();
+    for (index, src_line) in src_lines.enumerate() {
+        if let Ok(text) = src_line {
+            lines.push(Line {
+                index: 1 + (index as u32),
+                text: html::helper::replace_some_macros(&text, &macros),
+                file: Some(file.clone()),
+            });
+        }
+    }
+
+    let mut stack = stack.to_vec();
+    stack.push(canonical_path);
+    transform_includes_in_file(lines, &path, &stack)
+}
+
+// Describe the cycle (e.g. "a.bs -> b.bs -> a.bs") made by including the file from the
+// stack of files that include each other, if it is already in it.
+fn find_include_cycle(stack: &[PathBuf], path: &Path) -> Option<String> {
+    if !stack.iter().any(|p| p == path) {
+        return None;
+    }
+    let cycle = stack
+        .iter()
+        .map(|p| p.as_path())
+        .chain(std::iter::once(path))
+        .map(|p| p.display().to_string())
+        .collect::<Vec<String>>()
+        .join(" -> ");
+    Some(cycle)
+}
+
+// Insert the escaped contents of a file (e.g. "<pre include-code=foo.js show=10-25>"),
+// optionally trimmed to a range of lines. Numbered lines start at the range, unless the
+// block sets its own "line-start".
+pub fn transform_include_code(block: &Block, doc: &Spec) -> Vec<String> {
+    let path = resolve_path(&include_attr(block), &block_file(block, doc));
+    let text = read_included_file(&path, block);
+    let lines: Vec<&str> = text.lines().collect();
+
+    let (start, end) = match block.attrs.get("show") {
+        Some(show) => parse_line_range(show, lines.len(), block),
+        None => (1, lines.len()),
+    };
+    let code = lines[start - 1..end]
+        .iter()
+        .map(|line| html::helper::escape_html(line))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut attrs = block.attrs.clone();
+    attrs.remove("include-code");
+    if attrs.remove("show").is_some() && !attrs.contains_key("line-start") {
+        attrs.insert("line-start".to_owned(), start.to_string());
+    }
+    let attrs = attrs
+        .iter()
+        .map(|(key, val)| format!(" {}=\"{}\"", key, html::helper::escape_html(val)))
+        .collect::<String>();
+
+    vec![format!("<pre{}>{}</pre>", attrs, code)]
+}
+
+// Insert the contents of a file as they are (e.g. "<pre include-raw=foo.html></pre>").
+pub fn transform_include_raw(block: &Block, doc: &Spec) -> Vec<String> {
+    let path = resolve_path(&include_attr(block), &block_file(block, doc));
+    let text = read_included_file(&path, block);
+    text.lines().map(|line| line.to_owned()).collect()
+}
+
+// Get the file named by the attribute of the block type (e.g. "include-code=foo.js").
+fn include_attr(block: &Block) -> String {
+    match block.attrs.get(block.block_type) {
+        Some(path) if !path.trim().is_empty() => path.to_owned(),
+        _ => die!(
+            "The {} block needs an {}='' attribute naming the file to include.",
+            block.block_type,
+            block.block_type;
+            Some(&block.location)
+        ),
+    }
+}
+
+// Get the file that the block is in.
+fn block_file(block: &Block, doc: &Spec) -> PathBuf {
+    match block.location.file {
+        Some(ref file) => PathBuf::from(file.as_ref()),
+        None => PathBuf::from(doc.infile),
+    }
+}
+
+fn resolve_path(path: &str, including_file: &Path) -> PathBuf {
+    including_file
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(path.trim())
+}
+
+fn read_included_file(path: &Path, block: &Block) -> String {
+    match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(_) => die!(
+            "Couldn't find the include file \"{}\".",
+            path.display();
+            Some(&block.location)
+        ),
+    }
+}
+
+// Parse a 1-based inclusive line range like "10-25", "10-", "-25" or "10".
+fn parse_line_range(show: &str, line_count: usize, block: &Block) -> (usize, usize) {
+    let parse_bound = |bound: &str, default: usize| -> usize {
+        let bound = bound.trim();
+        if bound.is_empty() {
+            return default;
+        }
+        match bound.parse::<usize>() {
+            Ok(bound) => bound,
+            Err(_) => die!(
+                "The show='{}' attribute must be a line range like \"10-25\".",
+                show;
+                Some(&block.location)
+            ),
+        }
+    };
+
+    let (start, end) = match show.find('-') {
+        Some(pos) => (
+            parse_bound(&show[..pos], 1),
+            parse_bound(&show[pos + 1..], line_count),
+        ),
+        None => {
+            let line = parse_bound(show, 1);
+            (line, line)
+        }
+    };
+
+    if start < 1 || start > end || end > line_count {
+        die!(
+            "The show='{}' attribute is outside of the {} lines of the included file.",
+            show,
+            line_count;
+            Some(&block.location)
+        );
+    }
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::Location;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_find_include_cycle() {
+        let stack = vec![PathBuf::from("a.bs"), PathBuf::from("b.bs")];
+        assert_eq!(find_include_cycle(&stack, Path::new("c.bs")), None);
+        assert_eq!(
+            find_include_cycle(&stack, Path::new("a.bs")),
+            Some("a.bs -> b.bs -> a.bs".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_line_range() {
+        let block = Block {
+            block_type: "include-code",
+            attrs: BTreeMap::new(),
+            lines: Vec::new(),
+            location: Location::default(),
+        };
+        assert_eq!(parse_line_range("10-25", 30, &block), (10, 25));
+        assert_eq!(parse_line_range(" 10 - ", 30, &block), (10, 30));
+        assert_eq!(parse_line_range("-25", 30, &block), (1, 25));
+        assert_eq!(parse_line_range("7", 30, &block), (7, 7));
+        assert_eq!(parse_line_range("30-30", 30, &block), (30, 30));
+    }
+
+    #[test]
+    fn test_transform_includes() {
+        let dir = std::env::temp_dir().join(format!("bikeshed-include-{}", std::process::id()));
+        fs::create_dir_all(dir.join("parts")).unwrap();
+        fs::write(
+            dir.join("parts/a.bs"),
+            "<p>a [WHO]</p>\n<pre class=include>path: b.bs</pre>\n",
+        )
+        .unwrap();
+        fs::write(dir.join("parts/b.bs"), "<p>b</p>\n").unwrap();
+
+        let infile = dir.join("index.bs");
+        let lines: Vec<Line> = [
+            "<pre class=include>",
+            "path: parts/a.bs",
+            "macro-who: me",
+            "</pre>",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, text)| Line {
+            index: i as u32 + 1,
+            text: text.to_string(),
+            file: None,
+        })
+        .collect();
+        let lines = transform_includes(lines, infile.to_str().unwrap());
+        let texts: Vec<&str> = lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(texts, vec!["<p>a me</p>", "<p>b</p>"]);
+        assert!(lines[1].file.as_ref().unwrap().ends_with("b.bs"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
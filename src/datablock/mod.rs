@@ -0,0 +1,358 @@
+mod argumentdef;
+mod descdef;
+mod elementdef;
+pub mod include;
+mod propdef;
+mod railroad;
+
+use regex::Regex;
+use std::collections::BTreeMap;
+
+use crate::html;
+use crate::line::{Line, Location};
+use crate::spec::Spec;
+use crate::util::regex::PAIR_REG;
+
+type Transformer = fn(&Block, &Spec) -> Vec<String>;
+
+lazy_static! {
+    // block type => transformer
+    static ref TRANSFORMERS: BTreeMap<&'static str, Transformer> = {
+        let mut transformers: BTreeMap<&'static str, Transformer> = BTreeMap::new();
+        transformers.insert("propdef", propdef::transform_propdef);
+        transformers.insert("descdef", descdef::transform_descdef);
+        transformers.insert("elementdef", elementdef::transform_elementdef);
+        transformers.insert("argumentdef", argumentdef::transform_argumentdef);
+        transformers.insert("include-code", include::transform_include_code);
+        transformers.insert("include-raw", include::transform_include_raw);
+        transformers.insert("railroad", railroad::transform_railroad);
+        transformers
+    };
+}
+
+// Blocks whose output is inserted as it is, without markup shorthands or macros.
+const VERBATIM_BLOCK_TYPES: [&str; 2] = ["include-code", "include-raw"];
+
+#[derive(Debug)]
+pub struct Block {
+    pub block_type: &'static str,
+    pub attrs: BTreeMap<String, String>,
+    pub lines: Vec<Line>,
+    pub location: Location,
+}
+
+impl Block {
+    pub fn has_class(&self, class: &str) -> bool {
+        self.attrs
+            .get("class")
+            .is_some_and(|val| val.split_whitespace().any(|c| c == class))
+    }
+}
+
+pub fn transform_data_blocks(doc: &mut Spec) {
+    let lines = std::mem::take(&mut doc.lines);
+    let block_types: Vec<&'static str> = TRANSFORMERS.keys().cloned().collect();
+    let mut verbatim_blocks: Vec<String> = Vec::new();
+
+    doc.lines = scan_blocks(lines, &block_types, |block| {
+        let transform = TRANSFORMERS.get(block.block_type).unwrap();
+        let mut new_lines = transform(&block, doc);
+        // verbatim output is set aside, and a placeholder comment, which the later passes
+        // leave alone, takes its place
+        if VERBATIM_BLOCK_TYPES.contains(&block.block_type) {
+            verbatim_blocks.push(new_lines.join("\n"));
+            new_lines = vec![verbatim_placeholder(verbatim_blocks.len() - 1)];
+        }
+        new_lines
+            .into_iter()
+            .map(|text| Line {
+                index: block.location.index,
+                text,
+                file: block.location.file.clone(),
+            })
+            .collect()
+    });
+    doc.verbatim_blocks = verbatim_blocks;
+}
+
+// Put the verbatim output of the blocks back in place of their placeholders.
+pub fn restore_verbatim_blocks(html: &str, verbatim_blocks: &[String]) -> String {
+    let mut html = html.to_owned();
+    for (i, text) in verbatim_blocks.iter().enumerate() {
+        html = html.replacen(&verbatim_placeholder(i), text, 1);
+    }
+    html
+}
+
+fn verbatim_placeholder(index: usize) -> String {
+    format!("<!--verbatim-block-{}-->", index)
+}
+
+// Find the blocks of the given types (e.g. "<pre class=propdef>...</pre>"), and replace
+// each of them with the lines returned by the handler.
+pub fn scan_blocks(
+    lines: Vec<Line>,
+    block_types: &[&'static str],
+    mut handle: impl FnMut(Block) -> Vec<Line>,
+) -> Vec<Line> {
+    lazy_static! {
+        // begin tag reg
+        static ref BEGIN_TAG_REG: Regex = Regex::new(r"^\s*<(pre|xmp)(\s[^>]*)?>").unwrap();
+    }
+
+    let mut new_lines: Vec<Line> = Vec::new();
+    let mut iter = lines.into_iter();
+
+    while let Some(line) = iter.next() {
+        let caps = match BEGIN_TAG_REG.captures(&line.text) {
+            Some(caps) => caps,
+            None => {
+                new_lines.push(line);
+                continue;
+            }
+        };
+
+        let tag_name = caps[1].to_lowercase();
+        let start_tag = caps[0].to_owned();
+        let attrs = html::helper::parse_attributes(&start_tag);
+        // the type is given by a class (e.g. "<pre class=propdef>") or by an attribute
+        // (e.g. "<pre include-code=foo.js>")
+        let block_type = attrs
+            .get("class")
+            .and_then(|class| {
+                class
+                    .split_whitespace()
+                    .find_map(|c| block_types.iter().find(|block_type| **block_type == c))
+            })
+            .or_else(|| {
+                block_types
+                    .iter()
+                    .find(|block_type| attrs.contains_key(**block_type))
+            });
+        let block_type = match block_type {
+            Some(block_type) => *block_type,
+            None => {
+                new_lines.push(line);
+                continue;
+            }
+        };
+
+        // collect the contents of the block
+        let end_tag = format!("</{}>", tag_name);
+        let mut block_lines: Vec<Line> = Vec::new();
+        let mut rest: Option<Line> = None;
+        let mut next_line = Some(line.with_text(line.text[start_tag.len()..].to_owned()));
+
+        while let Some(line) = next_line {
+            if let Some(pos) = line.text.find(&end_tag) {
+                block_lines.push(line.with_text(line.text[..pos].to_owned()));
+                rest = Some(line.with_text(line.text[pos + end_tag.len()..].to_owned()));
+                break;
+            }
+            block_lines.push(line);
+            next_line = iter.next();
+        }
+
+        if rest.is_none() {
+            die!("Saw the start tag of a {} block, but never its end tag.", block_type; Some(line.location()));
+        }
+
+        let block = Block {
+            block_type,
+            attrs,
+            lines: block_lines
+                .into_iter()
+                .filter(|line| !line.text.trim().is_empty())
+                .collect(),
+            location: line.location(),
+        };
+        new_lines.extend(handle(block));
+
+        if let Some(rest) = rest {
+            if !rest.text.trim().is_empty() {
+                new_lines.push(rest);
+            }
+        }
+    }
+
+    new_lines
+}
+
+// Parse the "Key: val" lines of a definition block, keeping their order.
+pub fn parse_def_block(block: &Block) -> Vec<(String, String)> {
+    let mut pairs: Vec<(String, String)> = Vec::new();
+
+    for (key, val) in parse_pairs(block) {
+        let key = normalize_key(&key);
+        match pairs.iter_mut().find(|(k, _)| *k == key) {
+            Some(pair) => {
+                pair.1.push('\n');
+                pair.1.push_str(&val);
+            }
+            None => pairs.push((key, val)),
+        }
+    }
+
+    pairs
+}
+
+// Parse the "key: val" lines of a block as written. The block may be indented as a whole
+// (e.g. inside a "<section>"), and lines indented past the rest of it continue the value of
+// the previous key.
+pub fn parse_pairs(block: &Block) -> Vec<(String, String)> {
+    let indent_of = |line: &Line| line.text.len() - line.text.trim_start().len();
+    // text on the line of the start tag says nothing about the indentation of the block
+    let base_indent = block
+        .lines
+        .iter()
+        .filter(|line| line.index != block.location.index)
+        .map(indent_of)
+        .min()
+        .unwrap_or(0);
+    let mut pairs: Vec<(String, String)> = Vec::new();
+
+    for line in &block.lines {
+        let is_continuation = indent_of(line) > base_indent && !pairs.is_empty();
+        if is_continuation {
+            let last = pairs.last_mut().unwrap();
+            last.1.push('\n');
+            last.1.push_str(line.text.trim());
+        } else if let Some(caps) = PAIR_REG.captures(&line.text) {
+            pairs.push((caps[1].trim().to_owned(), caps[2].trim().to_owned()));
+        } else {
+            die!("Incorrectly formatted {} line: \"{}\".", block.block_type, line.text.trim(); Some(line.location()));
+        }
+    }
+
+    pairs
+}
+
+fn normalize_key(key: &str) -> String {
+    let key = key.trim().to_lowercase();
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => key,
+    }
+}
+
+pub fn get_val<'a>(pairs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, val)| val.as_str())
+}
+
+// Split a comma-separated list of names (e.g. "Name: margin-top, margin-bottom").
+pub fn split_names(val: &str) -> Vec<String> {
+    val.split(',')
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_block(block_type: &'static str, texts: &[&str]) -> Block {
+        Block {
+            block_type,
+            attrs: BTreeMap::new(),
+            lines: texts
+                .iter()
+                .enumerate()
+                .map(|(i, text)| Line {
+                    index: i as u32 + 2,
+                    text: text.to_string(),
+                    file: None,
+                })
+                .collect(),
+            location: Location::default(),
+        }
+    }
+
+    fn pair(key: &str, val: &str) -> (String, String) {
+        (key.to_owned(), val.to_owned())
+    }
+
+    #[test]
+    fn test_parse_pairs() {
+        let block = new_block("propdef", &["Name: foo", "Value: a |", "  b", "Initial: a"]);
+        assert_eq!(
+            parse_pairs(&block),
+            vec![
+                pair("Name", "foo"),
+                pair("Value", "a |\nb"),
+                pair("Initial", "a")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pairs_of_indented_block() {
+        let block = new_block(
+            "propdef",
+            &[
+                "    Name: foo",
+                "    Value: a |",
+                "      b",
+                "    Initial: a",
+            ],
+        );
+        assert_eq!(
+            parse_pairs(&block),
+            vec![
+                pair("Name", "foo"),
+                pair("Value", "a |\nb"),
+                pair("Initial", "a")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pairs_after_start_tag() {
+        // "<pre class=propdef>Name: foo" keeps the first pair on the line of the start tag
+        let mut block = new_block("propdef", &["Name: foo", "    Value: a", "    Initial: a"]);
+        block.location.index = block.lines[0].index;
+        assert_eq!(
+            parse_pairs(&block),
+            vec![
+                pair("Name", "foo"),
+                pair("Value", "a"),
+                pair("Initial", "a")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_def_block() {
+        let block = new_block("propdef", &["name: foo", "VALUE: a", "Value: b"]);
+        assert_eq!(
+            parse_def_block(&block),
+            vec![pair("Name", "foo"), pair("Value", "a\nb")]
+        );
+    }
+
+    #[test]
+    fn test_restore_verbatim_blocks() {
+        let html = format!(
+            "<p>{}</p>{}",
+            verbatim_placeholder(0),
+            verbatim_placeholder(1)
+        );
+        let blocks = vec!["[TITLE]".to_owned(), "<pre>'a'</pre>".to_owned()];
+        assert_eq!(
+            restore_verbatim_blocks(&html, &blocks),
+            "<p>[TITLE]</p><pre>'a'</pre>"
+        );
+    }
+
+    #[test]
+    fn test_split_names() {
+        assert_eq!(
+            split_names("margin-top, margin-bottom,"),
+            vec!["margin-top", "margin-bottom"]
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use regex::{Captures, Regex};
+
+use super::{get_val, parse_def_block, split_names, Block};
+use crate::shorthand::css;
+use crate::spec::Spec;
+use crate::util;
+
+pub fn transform_propdef(block: &Block, _doc: &Spec) -> Vec<String> {
+    let pairs = parse_def_block(block);
+    let names = get_val(&pairs, "Name").map_or(Vec::new(), split_names);
+    let is_partial = block.has_class("partial") || get_val(&pairs, "New values").is_some();
+
+    // Entries with a default value are optional, and the others are required.
+    let keys: Vec<(&str, Option<&str>)> = if is_partial {
+        vec![("Name", None), ("New values", None)]
+    } else if block.has_class("shorthand") {
+        vec![
+            ("Name", None),
+            ("Value", None),
+            ("Initial", Some("see individual properties")),
+            ("Applies to", Some("see individual properties")),
+            ("Inherited", Some("see individual properties")),
+            ("Percentages", Some("see individual properties")),
+            ("Computed value", Some("see individual properties")),
+            ("Animation type", Some("see individual properties")),
+            ("Canonical order", Some("per grammar")),
+        ]
+    } else {
+        vec![
+            ("Name", None),
+            ("Value", None),
+            ("Initial", None),
+            ("Applies to", Some("all elements")),
+            ("Inherited", None),
+            ("Percentages", Some("n/a")),
+            ("Computed value", Some("as specified")),
+            ("Canonical order", Some("per grammar")),
+            ("Animation type", None),
+        ]
+    };
+
+    let mut new_lines: Vec<String> = Vec::new();
+    new_lines.push(format!(
+        "<table class=\"def propdef{}\" data-link-for-hint=\"{}\">",
+        if is_partial { " partial" } else { "" },
+        names.first().map_or("", |name| name.as_str()),
+    ));
+    new_lines.push(String::from("<tbody>"));
+
+    for (key, default) in keys.iter() {
+        let val = match get_val(&pairs, key).or(*default) {
+            Some(val) => val,
+            None => die!(
+                "The propdef for \"{}\" is missing a \"{}\" line.",
+                names.join(", "),
+                key;
+                Some(&block.location)
+            ),
+        };
+        new_lines.push(match *key {
+            "Name" if is_partial => format!(
+                "<tr><th>Name:<td>{}",
+                names
+                    .iter()
+                    .map(|name| format!(
+                        "<a class=\"property\" data-link-type=\"property\">{}</a>",
+                        name
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            "Name" => format!(
+                "<tr><th>Name:<td>{}",
+                names
+                    .iter()
+                    .map(|name| format!(
+                        "<dfn class=\"css\" data-dfn-type=\"property\" data-export id=\"propdef-{0}\">{0}</dfn>",
+                        name
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            "Value" | "New values" => format!(
+                "<tr class=\"value\"><th>{}:<td class=\"prod\">{}",
+                key,
+                link_value_grammar(val, names.first().map_or("", |name| name.as_str()))
+            ),
+            _ => format!("<tr><th>{}:<td>{}", key, val),
+        });
+    }
+
+    // display unknown entries in the order they were specified
+    for (key, val) in pairs.iter() {
+        if keys.iter().all(|(k, _)| k != key) {
+            new_lines.push(format!("<tr><th>{}:<td>{}", key, val));
+        }
+    }
+
+    new_lines.push(String::from("</table>"));
+    new_lines
+}
+
+// Link the keywords, functions, types and property references of a CSS value definition
+// (e.g. "auto | <<length>>") to their definitions, whatever the CSS shorthands are set to.
+// Markup and combinators are kept as they are.
+pub fn link_value_grammar(val: &str, link_for: &str) -> String {
+    lazy_static! {
+        static ref TOKEN_REG: Regex = Regex::new(
+            r"<<([^<>]*)>>|<[^>]*>|'(-*[a-zA-Z@][\w-]*(?:/-*[a-zA-Z][\w-]*)?)'|'[^']*'|&[a-zA-Z]+;|(-*[a-zA-Z_][\w-]*)(\()?"
+        )
+        .unwrap();
+    }
+
+    let replacer = |caps: &Captures| -> String {
+        if let Some(inner) = caps.get(1) {
+            return css::link_type(inner.as_str());
+        }
+        if let Some(name) = caps.get(2) {
+            return css::link_property(name.as_str());
+        }
+        let name = match caps.get(3) {
+            Some(name) => name.as_str(),
+            None => return caps[0].to_owned(),
+        };
+        if caps.get(4).is_some() {
+            format!(
+                "<a class=\"css\" data-link-type=\"function\" data-lt=\"{0}()\">{0}(</a>",
+                name
+            )
+        } else {
+            format!(
+                "<a class=\"css\" data-link-type=\"value\" data-link-for=\"{}\">{}</a>",
+                link_for, name
+            )
+        }
+    };
+    util::regex::replace_all(&TOKEN_REG, val, replacer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_value_grammar() {
+        assert_eq!(
+            link_value_grammar("auto | <<length>>", "width"),
+            "<a class=\"css\" data-link-type=\"value\" data-link-for=\"width\">auto</a> | \
+             <a class=\"production\" data-link-type=\"type\">&lt;length></a>"
+        );
+        assert_eq!(
+            link_value_grammar("calc( 'height' ) | ','", "width"),
+            "<a class=\"css\" data-link-type=\"function\" data-lt=\"calc()\">calc(</a> \
+             <a class=\"property\" data-link-type=\"property\">height</a> ) | ','"
+        );
+    }
+
+    #[test]
+    fn test_link_value_grammar_keeps_entities() {
+        assert_eq!(
+            link_value_grammar("a &amp;&amp; [ b ]#", ""),
+            "<a class=\"css\" data-link-type=\"value\" data-link-for=\"\">a</a> &amp;&amp; \
+             [ <a class=\"css\" data-link-type=\"value\" data-link-for=\"\">b</a> ]#"
+        );
+    }
+}
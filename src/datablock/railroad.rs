@@ -0,0 +1,13 @@
+use super::Block;
+use crate::railroad;
+use crate::spec::Spec;
+
+// Render a railroad diagram written in the railroad DSL (e.g. "<pre class=railroad>")
+// as an inline SVG.
+pub fn transform_railroad(block: &Block, _doc: &Spec) -> Vec<String> {
+    let diagram = railroad::parse_diagram(&block.lines);
+    vec![format!(
+        "<div class=\"railroad\">{}</div>",
+        railroad::render_diagram(&diagram)
+    )]
+}
@@ -0,0 +1,21 @@
+use super::{lex_with_rules, Class, Rule, Token};
+
+lazy_static! {
+    static ref RULES: Vec<Rule> = vec![
+        Rule::new(r"/\*[\s\S]*?\*/", Class::Fixed("c")),
+        Rule::new(r#""(?:[^"\\\n]|\\.)*"|'(?:[^'\\\n]|\\.)*'"#, Class::Fixed("s")),
+        Rule::new(r"@[\w-]+", Class::Fixed("k")),
+        Rule::new(r"!\s*important\b", Class::Fixed("k")),
+        Rule::new(r"#[0-9a-fA-F]{3,8}\b", Class::Fixed("m")),
+        Rule::new(r"[+-]?(?:\d+\.?\d*|\.\d+)(?:[eE][+-]?\d+)?(?:%|[a-zA-Z]+)?", Class::Fixed("m")),
+        // a property is followed by ":" and a space, which tells it from a pseudo-class
+        Rule::new(r"(?P<t>-*[a-zA-Z][\w-]*)\s*:(?:\s|$)", Class::Fixed("na")),
+        Rule::new(r"(?P<t>[\w-]+)\(", Class::Fixed("nf")),
+        Rule::new(r"\.-?[a-zA-Z_][\w-]*", Class::Fixed("nc")),
+        Rule::new(r"-*[a-zA-Z_][\w-]*", Class::Word(|_| None)),
+    ];
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    lex_with_rules(text, &RULES)
+}
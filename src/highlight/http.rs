@@ -0,0 +1,16 @@
+use super::{lex_with_rules, At, Class, Rule, Token};
+
+lazy_static! {
+    static ref RULES: Vec<Rule> = vec![
+        Rule::new_at(r"(?P<t>[A-Za-z][\w-]*):", Class::Fixed("na"), At::LineStart),
+        // the method of a request line (e.g. "GET /foo HTTP/1.1")
+        Rule::new_at(r"(?P<t>[A-Z]+) ", Class::Fixed("k"), At::LineStart),
+        Rule::new(r"HTTP/\d(?:\.\d)?", Class::Fixed("kt")),
+        Rule::new(r"\d+", Class::Fixed("m")),
+        Rule::new(r"[A-Za-z_][\w-]*", Class::Word(|_| None)),
+    ];
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    lex_with_rules(text, &RULES)
+}
@@ -0,0 +1,77 @@
+use super::{lex_with_rules, Class, Rule, Token};
+
+const KEYWORDS: [&str; 40] = [
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "export",
+    "extends",
+    "finally",
+    "for",
+    "from",
+    "function",
+    "get",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "let",
+    "new",
+    "of",
+    "return",
+    "set",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "yield",
+];
+
+const CONSTANTS: [&str; 6] = ["true", "false", "null", "undefined", "NaN", "Infinity"];
+
+lazy_static! {
+    static ref RULES: Vec<Rule> = vec![
+        Rule::new(r"//[^\n]*|/\*[\s\S]*?\*/", Class::Fixed("c")),
+        Rule::new(
+            r#""(?:[^"\\\n]|\\.)*"|'(?:[^'\\\n]|\\.)*'|`(?:[^`\\]|\\[\s\S])*`"#,
+            Class::Fixed("s")
+        ),
+        Rule::new(
+            r"(?:0[xX][0-9a-fA-F_]+|0[bB][01_]+|0[oO][0-7_]+|(?:\d[\d_]*\.?[\d_]*|\.\d[\d_]*)(?:[eE][+-]?\d+)?)n?",
+            Class::Fixed("m")
+        ),
+        // a called function, unless it is a keyword like "if ("
+        Rule::new(r"(?P<t>[A-Za-z_$][\w$]*)\s*\(", Class::Word(|word| classify_word(word).or(Some("nf")))),
+        Rule::new(r"[A-Za-z_$][\w$]*", Class::Word(classify_word)),
+    ];
+}
+
+fn classify_word(word: &str) -> Option<&'static str> {
+    if KEYWORDS.contains(&word) {
+        Some("k")
+    } else if CONSTANTS.contains(&word) {
+        Some("kc")
+    } else {
+        None
+    }
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    lex_with_rules(text, &RULES)
+}
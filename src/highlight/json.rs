@@ -0,0 +1,18 @@
+use super::{lex_with_rules, Class, Rule, Token};
+
+lazy_static! {
+    static ref RULES: Vec<Rule> = vec![
+        // a key is a string followed by ":"
+        Rule::new(r#"(?P<t>"(?:[^"\\\n]|\\.)*")\s*:"#, Class::Fixed("na")),
+        Rule::new(r#""(?:[^"\\\n]|\\.)*""#, Class::Fixed("s")),
+        Rule::new(r"-?\d+(?:\.\d+)?(?:[eE][+-]?\d+)?", Class::Fixed("m")),
+        Rule::new(r"[A-Za-z_]\w*", Class::Word(|word| match word {
+            "true" | "false" | "null" => Some("kc"),
+            _ => None,
+        })),
+    ];
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    lex_with_rules(text, &RULES)
+}
@@ -0,0 +1,216 @@
+use kuchiki::NodeRef;
+
+use crate::html;
+use crate::spec::Spec;
+
+// Number the lines of the code blocks with a "line-numbers" attribute, and mark the lines
+// listed in a "line-highlight" attribute (e.g. "<pre line-numbers line-start=5
+// line-highlight='2-4,8'>"). Lines are numbered from "line-start", which the highlighted
+// lines refer to as well.
+pub fn process_line_numbers(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let pres: Vec<NodeRef> = document
+        .select("pre")
+        .unwrap()
+        .map(|pre| pre.as_node().clone())
+        .collect();
+
+    let mut numbered = false;
+    let mut highlighted = false;
+    for pre in pres.iter() {
+        let line_numbers = html::node::remove_attr(pre, "line-numbers").is_some();
+        let line_start = html::node::remove_attr(pre, "line-start");
+        let line_highlight = html::node::remove_attr(pre, "line-highlight");
+        if !line_numbers && line_highlight.is_none() {
+            continue;
+        }
+
+        let start = match line_start {
+            Some(val) => match val.trim().parse::<usize>() {
+                Ok(start) => start,
+                Err(_) => {
+                    warn!("The line-start='{}' attribute must be a number.", val);
+                    1
+                }
+            },
+            None => 1,
+        };
+        let highlighted_lines = match line_highlight {
+            Some(ref val) => parse_line_ranges(val),
+            None => Vec::new(),
+        };
+
+        wrap_lines(pre, start, line_numbers, &highlighted_lines);
+        if line_numbers {
+            html::node::add_class(pre, "line-numbered");
+            numbered = true;
+        }
+        if !highlighted_lines.is_empty() {
+            highlighted = true;
+        }
+    }
+
+    if numbered {
+        doc.extra_styles
+            .insert("line-numbers", include_str!("../style/line-numbers.css"));
+    }
+    if highlighted {
+        doc.extra_styles.insert(
+            "line-highlight",
+            include_str!("../style/line-highlight.css"),
+        );
+    }
+}
+
+// Parse a list of 1-based inclusive line ranges like "2-4,8".
+fn parse_line_ranges(val: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    for range in val.split(',').map(|range| range.trim()) {
+        if range.is_empty() {
+            continue;
+        }
+        let bounds = match range.find('-') {
+            Some(pos) => (range[..pos].trim().parse(), range[pos + 1..].trim().parse()),
+            None => (range.parse(), range.parse()),
+        };
+        match bounds {
+            (Ok(start), Ok(end)) if start <= end => ranges.push((start, end)),
+            _ => warn!(
+                "The line-highlight='{}' attribute must list line ranges like \"2-4,8\".",
+                val
+            ),
+        }
+    }
+    ranges
+}
+
+// Replace the contents of the element with a "<span class=line>" for each line.
+fn wrap_lines(
+    el: &NodeRef,
+    start: usize,
+    line_numbers: bool,
+    highlighted_lines: &[(usize, usize)],
+) {
+    let mut lines = split_lines(el);
+    // the newline that ends the last line isn't a line of its own
+    if lines.len() > 1 && lines.last().unwrap().is_empty() {
+        lines.pop();
+    }
+
+    let children: Vec<NodeRef> = el.children().collect();
+    for child in children.iter() {
+        child.detach();
+    }
+
+    let line_count = lines.len();
+    for (i, nodes) in lines.into_iter().enumerate() {
+        let number = start + i;
+        let line = html::node::new_element(
+            "span",
+            btreemap! {
+                "class" => "line".to_owned(),
+            },
+        );
+        if line_numbers {
+            html::node::set_attr(&line, "data-line", number.to_string());
+        }
+        if highlighted_lines
+            .iter()
+            .any(|(first, last)| *first <= number && number <= *last)
+        {
+            html::node::add_class(&line, "highlighted-line");
+        }
+        for node in nodes {
+            line.append(node);
+        }
+        // the newline stays in the text, so that copied code keeps its lines
+        if i + 1 < line_count {
+            line.append(NodeRef::new_text("\n"));
+        }
+        el.append(line);
+    }
+}
+
+// Split the contents of the node into lines. Elements that span several lines (e.g. the
+// span of a multi-line comment) are cloned into each of them.
+fn split_lines(node: &NodeRef) -> Vec<Vec<NodeRef>> {
+    let mut lines: Vec<Vec<NodeRef>> = vec![Vec::new()];
+
+    for child in node.children() {
+        if let Some(text) = child.as_text() {
+            for (i, part) in text.borrow().split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(Vec::new());
+                }
+                if !part.is_empty() {
+                    lines.last_mut().unwrap().push(NodeRef::new_text(part));
+                }
+            }
+        } else if child.as_element().is_some() {
+            let child_lines = split_lines(&child);
+            let is_multi_line = child_lines.len() > 1;
+            for (i, nodes) in child_lines.into_iter().enumerate() {
+                if i > 0 {
+                    lines.push(Vec::new());
+                }
+                if is_multi_line && nodes.is_empty() {
+                    continue;
+                }
+                let wrapper = NodeRef::new(child.data().clone());
+                for node in nodes {
+                    wrapper.append(node);
+                }
+                lines.last_mut().unwrap().push(wrapper);
+            }
+        } else {
+            lines
+                .last_mut()
+                .unwrap()
+                .push(html::node::deep_clone(&child));
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+
+    fn select_pre(html: &str) -> NodeRef {
+        let document = kuchiki::parse_html().one(html);
+        document.select_first("pre").unwrap().as_node().clone()
+    }
+
+    #[test]
+    fn test_parse_line_ranges() {
+        assert_eq!(parse_line_ranges("2-4, 8,"), vec![(2, 4), (8, 8)]);
+        assert_eq!(parse_line_ranges(" 3 - 5 "), vec![(3, 5)]);
+        assert_eq!(parse_line_ranges(""), Vec::new());
+        assert_eq!(parse_line_ranges("4-2, x, 6"), vec![(6, 6)]);
+    }
+
+    #[test]
+    fn test_wrap_lines() {
+        let pre = select_pre("<pre>a\nb\nc\n</pre>");
+        wrap_lines(&pre, 5, true, &[(6, 6)]);
+        assert_eq!(
+            pre.to_string(),
+            "<pre><span class=\"line\" data-line=\"5\">a\n</span>\
+             <span class=\"line highlighted-line\" data-line=\"6\">b\n</span>\
+             <span class=\"line\" data-line=\"7\">c</span></pre>"
+        );
+    }
+
+    #[test]
+    fn test_wrap_lines_of_multi_line_elements() {
+        let pre = select_pre("<pre>x <span class=c>/* a\nb */</span> y</pre>");
+        wrap_lines(&pre, 1, false, &[]);
+        assert_eq!(
+            pre.to_string(),
+            "<pre><span class=\"line\">x <span class=\"c\">/* a</span>\n</span>\
+             <span class=\"line\"><span class=\"c\">b */</span> y</span></pre>"
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use super::{lex_with_rules, Class, Rule, Token};
+
+lazy_static! {
+    static ref RULES: Vec<Rule> = vec![
+        Rule::new(r"<!--[\s\S]*?-->", Class::Fixed("c")),
+        Rule::new(r"(?i)<!doctype[^>]*>", Class::Fixed("cp")),
+        Rule::new(
+            r#"</?[A-Za-z][\w:-]*(?:[^<>"']|"[^"]*"|'[^']*')*>"#,
+            Class::Nested(lex_tag)
+        ),
+        Rule::new(r"&(?:#\d+|#[xX][0-9a-fA-F]+|[A-Za-z]\w*);", Class::Fixed("ni")),
+    ];
+    // rules for the inside of a tag
+    static ref TAG_RULES: Vec<Rule> = vec![
+        Rule::new(r"</?[A-Za-z][\w:-]*|/?>", Class::Fixed("nt")),
+        Rule::new(r#""[^"]*"|'[^']*'"#, Class::Fixed("s")),
+        Rule::new(r#"=\s*[^\s"'=<>`]+"#, Class::Nested(lex_unquoted_value)),
+        Rule::new(r#"[^\s"'>/=]+"#, Class::Fixed("na")),
+    ];
+}
+
+fn lex_tag(text: &str) -> Vec<Token> {
+    lex_with_rules(text, &TAG_RULES)
+}
+
+// Split "=foo" into the "=" and the value.
+fn lex_unquoted_value(text: &str) -> Vec<Token> {
+    let value_start = text[1..].find(|c: char| !c.is_whitespace()).unwrap_or(0) + 1;
+    vec![
+        (None, text[..value_start].to_owned()),
+        (Some("s"), text[value_start..].to_owned()),
+    ]
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    lex_with_rules(text, &RULES)
+}
@@ -0,0 +1,367 @@
+mod css;
+mod http;
+mod js;
+mod json;
+pub mod lines;
+mod markup;
+mod shell;
+mod webidl;
+
+use kuchiki::iter::NodeIterator;
+use kuchiki::NodeRef;
+use regex::Regex;
+
+use crate::html;
+use crate::spec::Spec;
+
+// A piece of highlighted code, with its class (e.g. "k" for keywords), or none for plain text.
+pub type Token = (Option<&'static str>, String);
+
+// Where a rule can match.
+#[derive(Clone, Copy, PartialEq)]
+pub enum At {
+    Anywhere,
+    LineStart,
+    // after whitespace, or at the start of the text
+    WordStart,
+}
+
+pub enum Class {
+    Fixed(&'static str),
+    // the class depends on the matched text (e.g. keywords among words), if it has one
+    Word(fn(&str) -> Option<&'static str>),
+    // the matched text is highlighted with its own rules (e.g. the attributes of a tag)
+    Nested(fn(&str) -> Vec<Token>),
+}
+
+// A rule that matches at the current position of the code. If the regex has a "t" group,
+// only that group is the token, which lets a rule look at the text that follows it (e.g.
+// the ":" after a property name).
+pub struct Rule {
+    pub reg: Regex,
+    pub class: Class,
+    pub at: At,
+}
+
+impl Rule {
+    pub fn new(reg: &str, class: Class) -> Rule {
+        Rule::new_at(reg, class, At::Anywhere)
+    }
+
+    pub fn new_at(reg: &str, class: Class, at: At) -> Rule {
+        Rule {
+            reg: Regex::new(&format!("^(?:{})", reg)).unwrap(),
+            class,
+            at,
+        }
+    }
+}
+
+// The highlighted languages, and the other names they are known by.
+const LANGUAGES: [(&str, &[&str]); 7] = [
+    ("css", &[]),
+    ("html", &["markup", "xml"]),
+    ("http", &[]),
+    ("js", &["javascript"]),
+    ("json", &[]),
+    ("shell", &["sh", "bash", "console"]),
+    ("webidl", &["idl"]),
+];
+
+// Find the language of a "highlight" attribute or a "Default Highlight" value.
+pub fn find_language(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(lang, aliases)| *lang == name || aliases.contains(&name.as_str()))
+        .map(|(lang, _)| *lang)
+}
+
+fn lex(lang: &str, text: &str) -> Vec<Token> {
+    match lang {
+        "css" => css::lex(text),
+        "html" => markup::lex(text),
+        "http" => http::lex(text),
+        "js" => js::lex(text),
+        "json" => json::lex(text),
+        "shell" => shell::lex(text),
+        "webidl" => webidl::lex(text),
+        _ => vec![(None, text.to_owned())],
+    }
+}
+
+// Split the text into tokens with the first rule that matches at each position. Text that
+// no rule matches is plain.
+pub fn lex_with_rules(text: &str, rules: &[Rule]) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut plain = String::new();
+    let mut pos = 0;
+
+    'outer: while pos < text.len() {
+        let rest = &text[pos..];
+        let prev = text[..pos].chars().last();
+
+        for rule in rules.iter() {
+            let allowed = match rule.at {
+                At::Anywhere => true,
+                At::LineStart => prev.is_none_or(|c| c == '\n'),
+                At::WordStart => prev.is_none_or(char::is_whitespace),
+            };
+            if !allowed {
+                continue;
+            }
+            let caps = match rule.reg.captures(rest) {
+                Some(caps) if !caps[0].is_empty() => caps,
+                _ => continue,
+            };
+            let matched = caps.name("t").unwrap_or_else(|| caps.get(0).unwrap());
+            let matched = &rest[..matched.end()];
+
+            match rule.class {
+                Class::Fixed(class) => push_token(&mut tokens, &mut plain, Some(class), matched),
+                Class::Word(classify) => {
+                    push_token(&mut tokens, &mut plain, classify(matched), matched)
+                }
+                Class::Nested(lex) => {
+                    for (class, text) in lex(matched) {
+                        push_token(&mut tokens, &mut plain, class, &text);
+                    }
+                }
+            }
+            pos += matched.len();
+            continue 'outer;
+        }
+
+        let c = rest.chars().next().unwrap();
+        plain.push(c);
+        pos += c.len_utf8();
+    }
+
+    if !plain.is_empty() {
+        tokens.push((None, plain));
+    }
+    tokens
+}
+
+fn push_token(
+    tokens: &mut Vec<Token>,
+    plain: &mut String,
+    class: Option<&'static str>,
+    text: &str,
+) {
+    match class {
+        Some(class) => {
+            if !plain.is_empty() {
+                tokens.push((None, std::mem::take(plain)));
+            }
+            tokens.push((Some(class), text.to_owned()));
+        }
+        None => plain.push_str(text),
+    }
+}
+
+// Highlight the code blocks with a "highlight" attribute (e.g. "<pre highlight=js>") or a
+// class that implies their language (e.g. "<pre class=idl>"), and the other code blocks if
+// the spec has a "Default Highlight". Code is split into spans classed by token type, and
+// styled by a stylesheet that is added when something is highlighted.
+pub fn highlight_code_blocks(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let pres: Vec<NodeRef> = document
+        .select("pre")
+        .unwrap()
+        .map(|pre| pre.as_node().clone())
+        .collect();
+
+    let mut highlighted = false;
+    for pre in pres.iter() {
+        if html::node::get_attr(pre, "nohighlight").is_some()
+            || html::node::has_class(pre, "nohighlight")
+        {
+            continue;
+        }
+        let name = match html::node::remove_attr(pre, "highlight")
+            .or_else(|| class_language(pre))
+            .or_else(|| doc.md.default_highlight.clone())
+        {
+            Some(name) => name,
+            None => continue,
+        };
+        let lang = match find_language(&name) {
+            Some(lang) => lang,
+            None => {
+                warn!("Unknown highlight language \"{}\".", name);
+                continue;
+            }
+        };
+
+        highlight_node(pre, lang);
+        html::node::add_class(pre, "highlight");
+        html::node::set_attr(pre, "data-lang", lang.to_owned());
+        highlighted = true;
+    }
+
+    if highlighted {
+        doc.extra_styles.insert(
+            "syntax-highlighting",
+            include_str!("../style/syntax-highlighting.css"),
+        );
+    }
+}
+
+// Find the language implied by the class of a code block: IDL blocks are WebIDL, and
+// "lang-foo" or "language-foo" names the language.
+fn class_language(el: &NodeRef) -> Option<String> {
+    let class = html::node::get_attr(el, "class")?;
+    class.split_whitespace().find_map(|c| {
+        if c == "idl" {
+            Some("webidl".to_owned())
+        } else {
+            c.strip_prefix("lang-")
+                .or_else(|| c.strip_prefix("language-"))
+                .map(|lang| lang.to_owned())
+        }
+    })
+}
+
+// Highlight each text node of the element on its own, so that markup in the code (e.g.
+// links) is kept.
+pub fn highlight_node(el: &NodeRef, lang: &str) {
+    let text_nodes: Vec<NodeRef> = el
+        .descendants()
+        .text_nodes()
+        .map(|text| text.as_node().clone())
+        .collect();
+
+    for text_node in text_nodes.iter() {
+        let text = text_node.as_text().unwrap().borrow().clone();
+        for (class, text) in lex(lang, &text) {
+            let node = match class {
+                Some(class) => html::node::new_text_element(
+                    "span",
+                    btreemap! {
+                        "class" => class.to_owned(),
+                    },
+                    &text,
+                ),
+                None => NodeRef::new_text(text),
+            };
+            text_node.insert_before(node);
+        }
+        text_node.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+
+    fn token(class: Option<&'static str>, text: &str) -> Token {
+        (class, text.to_owned())
+    }
+
+    fn select(html: &str, selector: &str) -> NodeRef {
+        let document = kuchiki::parse_html().one(html);
+        document.select_first(selector).unwrap().as_node().clone()
+    }
+
+    #[test]
+    fn test_find_language() {
+        assert_eq!(find_language("CSS"), Some("css"));
+        assert_eq!(find_language(" javascript "), Some("js"));
+        assert_eq!(find_language("idl"), Some("webidl"));
+        assert_eq!(find_language("bash"), Some("shell"));
+        assert_eq!(find_language("python"), None);
+    }
+
+    #[test]
+    fn test_class_language() {
+        assert_eq!(
+            class_language(&select("<pre class='idl extract'></pre>", "pre")),
+            Some("webidl".to_owned())
+        );
+        assert_eq!(
+            class_language(&select("<pre class=lang-css></pre>", "pre")),
+            Some("css".to_owned())
+        );
+        assert_eq!(
+            class_language(&select("<pre class='example language-js'></pre>", "pre")),
+            Some("js".to_owned())
+        );
+        assert_eq!(
+            class_language(&select("<pre class=example></pre>", "pre")),
+            None
+        );
+        assert_eq!(class_language(&select("<pre></pre>", "pre")), None);
+    }
+
+    #[test]
+    fn test_lex_with_rules() {
+        let rules = vec![
+            Rule::new_at("#[^\n]*", Class::Fixed("c"), At::LineStart),
+            Rule::new_at("-[a-z]", Class::Fixed("o"), At::WordStart),
+            Rule::new(r"(?P<t>\w+)\(", Class::Fixed("nf")),
+        ];
+        assert_eq!(
+            lex_with_rules("# note\nrun -v a-b f(x) # no", &rules),
+            vec![
+                token(Some("c"), "# note"),
+                token(None, "\nrun "),
+                token(Some("o"), "-v"),
+                token(None, " a-b "),
+                token(Some("nf"), "f"),
+                token(None, "(x) # no"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_webidl() {
+        assert_eq!(
+            lex("webidl", "readonly attribute DOMString? name = \"x\"; // c"),
+            vec![
+                token(Some("k"), "readonly"),
+                token(None, " "),
+                token(Some("k"), "attribute"),
+                token(None, " "),
+                token(Some("kt"), "DOMString"),
+                token(None, "? name = "),
+                token(Some("s"), "\"x\""),
+                token(None, "; "),
+                token(Some("c"), "// c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_css() {
+        assert_eq!(
+            lex("css", "a:hover { color: rgb(0 0 0) !important; }"),
+            vec![
+                token(None, "a:hover { "),
+                token(Some("na"), "color"),
+                token(None, ": "),
+                token(Some("nf"), "rgb"),
+                token(None, "("),
+                token(Some("m"), "0"),
+                token(None, " "),
+                token(Some("m"), "0"),
+                token(None, " "),
+                token(Some("m"), "0"),
+                token(None, ") "),
+                token(Some("k"), "!important"),
+                token(None, "; }"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_highlight_node() {
+        let pre = select("<pre>const <a>x</a> = 1;</pre>", "pre");
+        highlight_node(&pre, "js");
+        assert_eq!(
+            pre.to_string(),
+            "<pre><span class=\"k\">const</span> <a>x</a> = <span class=\"m\">1</span>;</pre>"
+        );
+    }
+}
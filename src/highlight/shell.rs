@@ -0,0 +1,33 @@
+use super::{lex_with_rules, At, Class, Rule, Token};
+
+const KEYWORDS: [&str; 17] = [
+    "if", "then", "else", "elif", "fi", "for", "in", "do", "done", "case", "esac", "while",
+    "until", "function", "return", "export", "local",
+];
+
+const BUILTINS: [&str; 10] = [
+    "cd", "echo", "exit", "printf", "pwd", "read", "set", "shift", "source", "unset",
+];
+
+lazy_static! {
+    static ref RULES: Vec<Rule> = vec![
+        // the prompt of an interactive session (e.g. "$ ls")
+        Rule::new_at(r"[$#] ", Class::Fixed("gp"), At::LineStart),
+        Rule::new_at(r"#[^\n]*", Class::Fixed("c"), At::WordStart),
+        Rule::new(r#""(?:[^"\\]|\\[\s\S])*"|'[^']*'"#, Class::Fixed("s")),
+        Rule::new(r"\$\{[^}\n]*\}|\$[A-Za-z_]\w*|\$[0-9@#?*!$-]", Class::Fixed("nv")),
+        Rule::new(r"[A-Za-z_][\w.-]*", Class::Word(|word| {
+            if KEYWORDS.contains(&word) {
+                Some("k")
+            } else if BUILTINS.contains(&word) {
+                Some("nb")
+            } else {
+                None
+            }
+        })),
+    ];
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    lex_with_rules(text, &RULES)
+}
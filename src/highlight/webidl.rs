@@ -0,0 +1,89 @@
+use super::{lex_with_rules, Class, Rule, Token};
+
+const KEYWORDS: [&str; 30] = [
+    "async",
+    "attribute",
+    "callback",
+    "const",
+    "constructor",
+    "deleter",
+    "dictionary",
+    "enum",
+    "getter",
+    "includes",
+    "inherit",
+    "interface",
+    "iterable",
+    "maplike",
+    "mixin",
+    "namespace",
+    "optional",
+    "or",
+    "partial",
+    "readonly",
+    "required",
+    "setlike",
+    "setter",
+    "static",
+    "stringifier",
+    "typedef",
+    "unrestricted",
+    "unsigned",
+    "long",
+    "short",
+];
+
+const TYPES: [&str; 21] = [
+    "any",
+    "bigint",
+    "boolean",
+    "byte",
+    "ByteString",
+    "DOMString",
+    "double",
+    "float",
+    "FrozenArray",
+    "object",
+    "ObservableArray",
+    "octet",
+    "Promise",
+    "record",
+    "sequence",
+    "symbol",
+    "undefined",
+    "USVString",
+    "void",
+    "ArrayBuffer",
+    "DataView",
+];
+
+const CONSTANTS: [&str; 6] = ["true", "false", "null", "Infinity", "-Infinity", "NaN"];
+
+lazy_static! {
+    static ref RULES: Vec<Rule> = vec![
+        Rule::new(r"//[^\n]*|/\*[\s\S]*?\*/", Class::Fixed("c")),
+        Rule::new(r#""[^"\n]*""#, Class::Fixed("s")),
+        Rule::new(
+            r"-?(?:0[xX][0-9a-fA-F]+|\d+\.?\d*(?:[eE][+-]?\d+)?|\.\d+(?:[eE][+-]?\d+)?)",
+            Class::Fixed("m")
+        ),
+        Rule::new(
+            r"_?[A-Za-z][\w-]*",
+            Class::Word(|word| {
+                if KEYWORDS.contains(&word) {
+                    Some("k")
+                } else if TYPES.contains(&word) {
+                    Some("kt")
+                } else if CONSTANTS.contains(&word) {
+                    Some("kc")
+                } else {
+                    None
+                }
+            })
+        ),
+    ];
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    lex_with_rules(text, &RULES)
+}
@@ -1,19 +1,206 @@
 use regex::{Captures, Regex};
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use crate::util;
 
-pub fn replace_macros(text: &String, macros: &HashMap<&'static str, String>) -> String {
-    lazy_static! {
-        static ref REG: Regex = Regex::new(r"\[(?P<inner_text>[A-Z0-9-]+)\]").unwrap();
+lazy_static! {
+    // "[[FOO]]" is not a macro, so the extra bracket is matched to skip it. Names need a
+    // letter, so that indexing like "arr[0]" is left alone.
+    static ref MACRO_REG: Regex = Regex::new(
+        r"(?P<escape>\\)?(?P<bracket>\[)?\[(?P<inner_text>[A-Z\d-]*[A-Z][A-Z\d-]*)(?P<optional>\?)?\]"
+    )
+    .unwrap();
+}
+
+// How the text macros of a piece of text are replaced.
+#[derive(Clone, Copy, PartialEq)]
+enum MacroMode {
+    // every macro, with escaped macros turned into literal text
+    All,
+    // every macro, with escaped macros left escaped
+    KeepEscapes,
+    // only the given macros, leaving the others and the escaped ones to a later pass
+    Partial,
+}
+
+// Replace the text macros (e.g. "[TITLE]"). Macros can be used in the text of other
+// macros, and macros without a definition are reported, unless they are optional (e.g.
+// "[TITLE?]"). An escaped macro (e.g. "\[TITLE]") is left as literal text.
+pub fn replace_macros(text: &str, macros: &HashMap<String, String>) -> String {
+    replace_all_macros(text, macros, MacroMode::All)
+}
+
+// Replace the text macros, but leave escaped macros escaped, for text that goes through
+// replace_macros again (e.g. metadata values, which are also macros).
+pub fn replace_macros_keeping_escapes(text: &str, macros: &HashMap<String, String>) -> String {
+    replace_all_macros(text, macros, MacroMode::KeepEscapes)
+}
+
+// Replace only the given macros (e.g. the "macro-foo: bar" parameters of an include), and
+// leave everything else to replace_macros.
+pub fn replace_some_macros(text: &str, macros: &HashMap<String, String>) -> String {
+    replace_all_macros(text, macros, MacroMode::Partial)
+}
+
+// Turn escaped macros into literal text (e.g. "\[TITLE]" into "[TITLE]").
+pub fn unescape_macros(text: &str) -> String {
+    util::regex::replace_all(&MACRO_REG, text, |caps: &Captures| -> String {
+        if caps.name("escape").is_some() {
+            caps[0][1..].to_string()
+        } else {
+            caps[0].to_string()
+        }
+    })
+}
+
+fn replace_all_macros(text: &str, macros: &HashMap<String, String>, mode: MacroMode) -> String {
+    let unknown_macros: RefCell<BTreeSet<String>> = RefCell::new(BTreeSet::new());
+    let text = expand_macros(text, macros, &[], mode, &unknown_macros);
+    for name in unknown_macros.borrow().iter() {
+        warn!("Found unknown text macro [{}].", name.to_uppercase());
     }
+    text
+}
+
+fn expand_macros(
+    text: &str,
+    macros: &HashMap<String, String>,
+    stack: &[String],
+    mode: MacroMode,
+    unknown_macros: &RefCell<BTreeSet<String>>,
+) -> String {
     let replacer = |caps: &Captures| -> String {
-        let inner_text = (&caps["inner_text"]).to_lowercase();
-        if macros.contains_key(inner_text.as_str()) {
-            macros.get(inner_text.as_str()).unwrap().to_string()
-        } else {
-            (&caps[0]).to_string()
+        if caps.name("escape").is_some() {
+            return if mode == MacroMode::All {
+                caps[0][1..].to_string()
+            } else {
+                caps[0].to_string()
+            };
+        }
+        if caps.name("bracket").is_some() {
+            return caps[0].to_string();
+        }
+        let inner_text = caps["inner_text"].to_lowercase();
+        if stack.contains(&inner_text) {
+            die!(
+                "Text macro [{}] is defined in terms of itself: {}.",
+                inner_text.to_uppercase(),
+                stack
+                    .iter()
+                    .chain(std::iter::once(&inner_text))
+                    .map(|name| format!("[{}]", name.to_uppercase()))
+                    .collect::<Vec<String>>()
+                    .join(" -> ")
+            );
+        }
+        match macros.get(&inner_text) {
+            Some(val) => {
+                let mut stack = stack.to_vec();
+                stack.push(inner_text);
+                expand_macros(val, macros, &stack, mode, unknown_macros)
+            }
+            None if mode == MacroMode::Partial => caps[0].to_string(),
+            None if caps.name("optional").is_some() => String::new(),
+            None => {
+                unknown_macros.borrow_mut().insert(inner_text);
+                caps[0].to_string()
+            }
         }
     };
-    util::regex::replace_all(&REG, text, replacer)
+    util::regex::replace_all(&MACRO_REG, text, replacer)
+}
+
+// Parse the attributes of a start tag (e.g. "<pre class=propdef>").
+pub fn parse_attributes(tag: &str) -> BTreeMap<String, String> {
+    lazy_static! {
+        static ref ATTR_REG: Regex =
+            Regex::new(r#"\s([\w-]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#).unwrap();
+    }
+    ATTR_REG
+        .captures_iter(tag)
+        .map(|caps| {
+            let val = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map_or("", |v| v.as_str());
+            (caps[1].to_lowercase(), val.to_owned())
+        })
+        .collect()
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_macros(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, val)| (name.to_string(), val.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_replace_macros() {
+        let macros = new_macros(&[("title", "Foo [LEVEL]"), ("level", "1"), ("x2", "y")]);
+        assert_eq!(replace_macros("[TITLE]: [X2]", &macros), "Foo 1: y");
+    }
+
+    #[test]
+    fn test_replace_macros_needs_a_letter() {
+        let macros = new_macros(&[("0", "zero")]);
+        assert_eq!(
+            replace_macros("arr[0] and arr[10]", &macros),
+            "arr[0] and arr[10]"
+        );
+    }
+
+    #[test]
+    fn test_replace_macros_skips_biblio_refs() {
+        let macros = new_macros(&[("dom", "DOM")]);
+        assert_eq!(replace_macros("[[DOM]]", &macros), "[[DOM]]");
+    }
+
+    #[test]
+    fn test_replace_macros_with_escapes_and_optional_macros() {
+        let macros = new_macros(&[("title", "Foo")]);
+        assert_eq!(
+            replace_macros("\\[TITLE] [TITLE] [FOO?]", &macros),
+            "[TITLE] Foo "
+        );
+        assert_eq!(
+            replace_macros_keeping_escapes("\\[TITLE] [TITLE]", &macros),
+            "\\[TITLE] Foo"
+        );
+        assert_eq!(
+            unescape_macros("\\[TITLE] \\[0] [TITLE]"),
+            "[TITLE] \\[0] [TITLE]"
+        );
+    }
+
+    #[test]
+    fn test_replace_macros_keeps_escapes_in_macros() {
+        // an escaped macro in the value of another macro is expanded once
+        let macros = new_macros(&[("title", "A \\[SHORTNAME] B"), ("shortname", "foo")]);
+        assert_eq!(replace_macros("[TITLE]", &macros), "A [SHORTNAME] B");
+    }
+
+    #[test]
+    fn test_replace_some_macros() {
+        let macros = new_macros(&[("foo", "bar [BAZ]")]);
+        assert_eq!(
+            replace_some_macros("[FOO] [TITLE] [TITLE?]", &macros),
+            "bar [BAZ] [TITLE] [TITLE?]"
+        );
+        assert_eq!(replace_some_macros("\\[FOO]", &macros), "\\[FOO]");
+    }
 }
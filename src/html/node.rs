@@ -1,3 +1,4 @@
+use kuchiki::traits::*;
 use kuchiki::{Attribute, ExpandedName, NodeRef};
 use markup5ever::{LocalName, QualName};
 
@@ -23,3 +24,81 @@ pub fn new_style(text: &str) -> NodeRef {
     el.append(NodeRef::new_text(text));
     el
 }
+
+pub fn new_text_element<I>(name: &str, attributes: I, text: &str) -> NodeRef
+where
+    I: IntoIterator<Item = (&'static str, String)>,
+{
+    let el = new_element(name, attributes);
+    el.append(NodeRef::new_text(text));
+    el
+}
+
+pub fn get_attr(el: &NodeRef, name: &str) -> Option<String> {
+    el.as_element()
+        .and_then(|data| data.attributes.borrow().get(name).map(|val| val.to_owned()))
+}
+
+pub fn set_attr(el: &NodeRef, name: &str, val: String) {
+    if let Some(data) = el.as_element() {
+        data.attributes.borrow_mut().insert(name, val);
+    }
+}
+
+pub fn remove_attr(el: &NodeRef, name: &str) -> Option<String> {
+    el.as_element()
+        .and_then(|data| data.attributes.borrow_mut().remove(name))
+        .map(|attr| attr.value)
+}
+
+pub fn is_element(el: &NodeRef, name: &str) -> bool {
+    el.as_element()
+        .is_some_and(|data| data.name.local.as_ref() == name)
+}
+
+pub fn has_class(el: &NodeRef, class: &str) -> bool {
+    get_attr(el, "class").is_some_and(|val| val.split_whitespace().any(|c| c == class))
+}
+
+pub fn add_class(el: &NodeRef, class: &str) {
+    if has_class(el, class) {
+        return;
+    }
+    let val = match get_attr(el, "class") {
+        Some(val) if !val.trim().is_empty() => format!("{} {}", val.trim(), class),
+        _ => class.to_owned(),
+    };
+    set_attr(el, "class", val);
+}
+
+pub fn remove_class(el: &NodeRef, class: &str) {
+    if let Some(val) = get_attr(el, "class") {
+        let val = val
+            .split_whitespace()
+            .filter(|c| *c != class)
+            .collect::<Vec<&str>>()
+            .join(" ");
+        if val.is_empty() {
+            remove_attr(el, "class");
+        } else {
+            set_attr(el, "class", val);
+        }
+    }
+}
+
+pub fn deep_clone(node: &NodeRef) -> NodeRef {
+    let new_node = NodeRef::new(node.data().clone());
+    for child in node.children() {
+        new_node.append(deep_clone(&child));
+    }
+    new_node
+}
+
+// Parse an HTML fragment (e.g. the contents of a boilerplate file) into its top-level nodes.
+pub fn parse_fragment(text: &str) -> Vec<NodeRef> {
+    let document = kuchiki::parse_html().one(text);
+    match document.select_first("body") {
+        Ok(body) => body.as_node().children().collect(),
+        Err(_) => Vec::new(),
+    }
+}
@@ -0,0 +1,313 @@
+use serde_json::{json, Value as Json};
+use std::fs;
+
+use super::{Argument, Definition, ExtAttr, IdlType, Member, TypeInner, Value};
+use crate::spec::Spec;
+
+// Write the parsed IDL of the spec as a JSON AST in the shape of webidl2.js (e.g.
+// "foo.idl.json" for "foo.html").
+pub fn write_json(doc: &Spec, outfile: &str) {
+    if outfile == "-" {
+        warn!("The IDL JSON is only written next to an output file.");
+        return;
+    }
+    let json_path = format!(
+        "{}.idl.json",
+        outfile.strip_suffix(".html").unwrap_or(outfile)
+    );
+
+    let definitions: Vec<Json> = doc.idl.iter().map(definition_to_json).collect();
+    let rendered = serde_json::to_string_pretty(&definitions).unwrap();
+    fs::write(json_path, rendered + "\n").expect("unable to write file");
+}
+
+fn definition_to_json(definition: &Definition) -> Json {
+    match definition {
+        Definition::Container {
+            kind,
+            name,
+            partial,
+            inheritance,
+            members,
+            ext_attrs,
+        } => json!({
+            "type": kind,
+            "name": name,
+            "partial": partial,
+            "inheritance": inheritance,
+            "members": members.iter().map(member_to_json).collect::<Vec<Json>>(),
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Definition::Enum {
+            name,
+            values,
+            ext_attrs,
+        } => json!({
+            "type": "enum",
+            "name": name,
+            "values": values
+                .iter()
+                .map(|value| json!({ "type": "enum-value", "value": value }))
+                .collect::<Vec<Json>>(),
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Definition::Typedef {
+            name,
+            idl_type,
+            ext_attrs,
+        } => json!({
+            "type": "typedef",
+            "name": name,
+            "idlType": type_to_json(idl_type, Some("typedef-type")),
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Definition::Callback {
+            name,
+            return_type,
+            arguments,
+            ext_attrs,
+        } => json!({
+            "type": "callback",
+            "name": name,
+            "idlType": type_to_json(return_type, Some("return-type")),
+            "arguments": arguments_to_json(arguments),
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Definition::Includes {
+            target,
+            includes,
+            ext_attrs,
+        } => json!({
+            "type": "includes",
+            "target": target,
+            "includes": includes,
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+    }
+}
+
+fn member_to_json(member: &Member) -> Json {
+    match member {
+        Member::Operation {
+            name,
+            return_type,
+            arguments,
+            special,
+            ext_attrs,
+        } => json!({
+            "type": "operation",
+            "name": name,
+            "idlType": return_type
+                .as_ref()
+                .map(|return_type| type_to_json(return_type, Some("return-type"))),
+            "arguments": arguments_to_json(arguments),
+            "special": special,
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Member::Constructor {
+            arguments,
+            ext_attrs,
+        } => json!({
+            "type": "constructor",
+            "arguments": arguments_to_json(arguments),
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Member::Attribute {
+            name,
+            idl_type,
+            special,
+            readonly,
+            ext_attrs,
+        } => json!({
+            "type": "attribute",
+            "name": name,
+            "idlType": type_to_json(idl_type, Some("attribute-type")),
+            "special": special,
+            "readonly": readonly,
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Member::Const {
+            name,
+            idl_type,
+            value,
+            ext_attrs,
+        } => json!({
+            "type": "const",
+            "name": name,
+            "idlType": type_to_json(idl_type, Some("const-type")),
+            "value": value_to_json(value),
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Member::Field {
+            name,
+            idl_type,
+            required,
+            default,
+            ext_attrs,
+        } => json!({
+            "type": "field",
+            "name": name,
+            "idlType": type_to_json(idl_type, Some("dictionary-type")),
+            "required": required,
+            "default": default.as_ref().map(value_to_json),
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+        Member::Declaration {
+            kind,
+            idl_types,
+            readonly,
+            is_async,
+            arguments,
+            ext_attrs,
+        } => json!({
+            "type": kind,
+            "idlType": idl_types
+                .iter()
+                .map(|idl_type| type_to_json(idl_type, None))
+                .collect::<Vec<Json>>(),
+            "readonly": readonly,
+            "async": is_async,
+            "arguments": arguments_to_json(arguments),
+            "extAttrs": ext_attrs_to_json(ext_attrs),
+        }),
+    }
+}
+
+fn arguments_to_json(arguments: &[Argument]) -> Vec<Json> {
+    arguments
+        .iter()
+        .map(|argument| {
+            json!({
+                "type": "argument",
+                "name": argument.name,
+                "idlType": type_to_json(&argument.idl_type, Some("argument-type")),
+                "optional": argument.optional,
+                "variadic": argument.variadic,
+                "default": argument.default.as_ref().map(value_to_json),
+                "extAttrs": ext_attrs_to_json(&argument.ext_attrs),
+            })
+        })
+        .collect()
+}
+
+// Types nested in generics and unions have no type of their own in webidl2.js.
+fn type_to_json(idl_type: &IdlType, type_name: Option<&str>) -> Json {
+    let inner = match idl_type.inner {
+        TypeInner::Name(ref name) => json!(name),
+        TypeInner::Types(ref types) => json!(types
+            .iter()
+            .map(|t| type_to_json(t, type_name.filter(|_| !idl_type.union)))
+            .collect::<Vec<Json>>()),
+    };
+    json!({
+        "type": type_name,
+        "generic": idl_type.generic,
+        "nullable": idl_type.nullable,
+        "union": idl_type.union,
+        "idlType": inner,
+        "extAttrs": ext_attrs_to_json(&idl_type.ext_attrs),
+    })
+}
+
+fn ext_attrs_to_json(ext_attrs: &[ExtAttr]) -> Vec<Json> {
+    ext_attrs
+        .iter()
+        .map(|ext_attr| {
+            let rhs = ext_attr.rhs.as_ref().map(|(rhs_type, values)| {
+                if *rhs_type == "identifier-list" {
+                    json!({
+                        "type": rhs_type,
+                        "value": values
+                            .iter()
+                            .map(|value| json!({ "value": value }))
+                            .collect::<Vec<Json>>(),
+                    })
+                } else {
+                    json!({ "type": rhs_type, "value": values[0] })
+                }
+            });
+            json!({
+                "type": "extended-attribute",
+                "name": ext_attr.name,
+                "rhs": rhs,
+                "arguments": arguments_to_json(&ext_attr.arguments),
+            })
+        })
+        .collect()
+}
+
+fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::String(value) => json!({ "type": "string", "value": value }),
+        Value::Number(value) => json!({ "type": "number", "value": value }),
+        Value::Boolean(value) => json!({ "type": "boolean", "value": value }),
+        Value::Null => json!({ "type": "null" }),
+        Value::Infinity(negative) => json!({ "type": "Infinity", "negative": negative }),
+        Value::NaN => json!({ "type": "NaN" }),
+        Value::Sequence => json!({ "type": "sequence", "value": [] }),
+        Value::Dictionary => json!({ "type": "dictionary" }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_type(generic: &str, nullable: bool, inner: TypeInner) -> IdlType {
+        IdlType {
+            generic: generic.to_owned(),
+            nullable,
+            union: false,
+            inner,
+            ext_attrs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_type_to_json() {
+        let long = new_type("", false, TypeInner::Name("long".to_owned()));
+        let sequence = new_type("sequence", true, TypeInner::Types(vec![long]));
+        assert_eq!(
+            type_to_json(&sequence, Some("argument-type")),
+            json!({
+                "type": "argument-type",
+                "generic": "sequence",
+                "nullable": true,
+                "union": false,
+                "idlType": [{
+                    "type": "argument-type",
+                    "generic": "",
+                    "nullable": false,
+                    "union": false,
+                    "idlType": "long",
+                    "extAttrs": [],
+                }],
+                "extAttrs": [],
+            })
+        );
+    }
+
+    #[test]
+    fn test_ext_attrs_to_json() {
+        let ext_attr = ExtAttr {
+            name: "Exposed".to_owned(),
+            rhs: Some((
+                "identifier-list",
+                vec!["Window".to_owned(), "Worker".to_owned()],
+            )),
+            arguments: Vec::new(),
+        };
+        assert_eq!(
+            ext_attrs_to_json(&[ext_attr]),
+            vec![json!({
+                "type": "extended-attribute",
+                "name": "Exposed",
+                "rhs": {
+                    "type": "identifier-list",
+                    "value": [{ "value": "Window" }, { "value": "Worker" }],
+                },
+                "arguments": [],
+            })]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+mod json;
+mod parse;
+
+use std::fmt;
+
+use crate::datablock::{self, Block};
+use crate::line::Line;
+
+pub use json::write_json;
+
+// A top-level IDL definition.
+#[derive(Debug, Clone)]
+pub enum Definition {
+    // interfaces, interface mixins, callback interfaces, namespaces and dictionaries
+    Container {
+        kind: &'static str,
+        name: String,
+        partial: bool,
+        inheritance: Option<String>,
+        members: Vec<Member>,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    Enum {
+        name: String,
+        values: Vec<String>,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    Typedef {
+        name: String,
+        idl_type: IdlType,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    Callback {
+        name: String,
+        return_type: IdlType,
+        arguments: Vec<Argument>,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    Includes {
+        target: String,
+        includes: String,
+        ext_attrs: Vec<ExtAttr>,
+    },
+}
+
+// A member of an interface, namespace or dictionary.
+#[derive(Debug, Clone)]
+pub enum Member {
+    Operation {
+        name: String,
+        // none for a plain "stringifier;"
+        return_type: Option<IdlType>,
+        arguments: Vec<Argument>,
+        special: &'static str,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    Constructor {
+        arguments: Vec<Argument>,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    Attribute {
+        name: String,
+        idl_type: IdlType,
+        special: &'static str,
+        readonly: bool,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    Const {
+        name: String,
+        idl_type: IdlType,
+        value: Value,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    Field {
+        name: String,
+        idl_type: IdlType,
+        required: bool,
+        default: Option<Value>,
+        ext_attrs: Vec<ExtAttr>,
+    },
+    // iterable, maplike and setlike declarations
+    Declaration {
+        kind: &'static str,
+        idl_types: Vec<IdlType>,
+        readonly: bool,
+        is_async: bool,
+        arguments: Vec<Argument>,
+        ext_attrs: Vec<ExtAttr>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Argument {
+    pub name: String,
+    pub idl_type: IdlType,
+    pub optional: bool,
+    pub variadic: bool,
+    pub default: Option<Value>,
+    pub ext_attrs: Vec<ExtAttr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IdlType {
+    // e.g. "sequence" for "sequence<long>"
+    pub generic: String,
+    pub nullable: bool,
+    pub union: bool,
+    pub inner: TypeInner,
+    pub ext_attrs: Vec<ExtAttr>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TypeInner {
+    Name(String),
+    // the parameters of a generic type, or the members of a union
+    Types(Vec<IdlType>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtAttr {
+    pub name: String,
+    // (type, values) of the right-hand side (e.g. ("identifier", ["Window"]) for
+    // "[Exposed=Window]")
+    pub rhs: Option<(&'static str, Vec<String>)>,
+    pub arguments: Vec<Argument>,
+}
+
+// A default or constant value.
+#[derive(Debug, Clone)]
+pub enum Value {
+    String(String),
+    Number(String),
+    Boolean(bool),
+    Null,
+    Infinity(bool),
+    NaN,
+    Sequence,
+    Dictionary,
+}
+
+impl fmt::Display for IdlType {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            TypeInner::Name(ref name) => write!(fmt, "{}", name)?,
+            TypeInner::Types(ref types) => {
+                let types = types.iter().map(|t| t.to_string()).collect::<Vec<String>>();
+                if self.union {
+                    write!(fmt, "({})", types.join(" or "))?;
+                } else {
+                    write!(fmt, "{}<{}>", self.generic, types.join(", "))?;
+                }
+            }
+        }
+        if self.nullable {
+            write!(fmt, "?")?;
+        }
+        Ok(())
+    }
+}
+
+// Parse the "<pre class=idl>" blocks of the source.
+pub fn parse_idl_blocks(lines: &[Line]) -> Vec<Definition> {
+    let mut blocks: Vec<Block> = Vec::new();
+    datablock::scan_blocks(lines.to_vec(), &["idl"], |block| {
+        blocks.push(block);
+        Vec::new()
+    });
+    blocks.iter().flat_map(parse::parse_block).collect()
+}
+
+// Whether an interface, namespace or dictionary (e.g. "Foo") is defined, including by a
+// partial definition.
+pub fn has_container(definitions: &[Definition], name: &str) -> bool {
+    definitions.iter().any(|definition| match definition {
+        Definition::Container { name: n, .. } => n == name,
+        _ => false,
+    })
+}
+
+// Find the argument lists of the overloads of a method (e.g. "bar" of "Foo"), including
+// those from partial interfaces. "constructor" finds the constructors.
+pub fn find_overloads<'a>(
+    definitions: &'a [Definition],
+    interface: &str,
+    method: &str,
+) -> Vec<&'a [Argument]> {
+    definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Container { name, members, .. } if name == interface => Some(members),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|member| match member {
+            Member::Operation {
+                name, arguments, ..
+            } if name == method => Some(arguments.as_slice()),
+            Member::Constructor { arguments, .. } if method == "constructor" => {
+                Some(arguments.as_slice())
+            }
+            _ => None,
+        })
+        .collect()
+}
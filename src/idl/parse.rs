@@ -0,0 +1,862 @@
+use regex::Regex;
+
+use super::{Argument, Definition, ExtAttr, IdlType, Member, TypeInner, Value};
+use crate::datablock::Block;
+use crate::line::Location;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenKind {
+    Identifier,
+    String,
+    Integer,
+    Decimal,
+    Other,
+}
+
+#[derive(Debug)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    location: Location,
+}
+
+// Types with parameters (e.g. "sequence<long>").
+const GENERIC_TYPES: [&str; 5] = [
+    "sequence",
+    "FrozenArray",
+    "ObservableArray",
+    "Promise",
+    "record",
+];
+
+// Parse the definitions of an IDL block.
+pub fn parse_block(block: &Block) -> Vec<Definition> {
+    let mut parser = Parser {
+        tokens: tokenize(block),
+        pos: 0,
+        end: block.location.clone(),
+    };
+    if let Some(last) = block.lines.last() {
+        parser.end = last.location();
+    }
+
+    let mut definitions: Vec<Definition> = Vec::new();
+    while parser.peek().is_some() {
+        definitions.push(parser.parse_definition());
+    }
+    definitions
+}
+
+fn tokenize(block: &Block) -> Vec<Token> {
+    lazy_static! {
+        static ref TOKEN_REG: Regex = Regex::new(concat!(
+            r"^(?:",
+            r"(?P<space>\s+|//.*|/\*.*?\*/)",
+            r#"|(?P<string>"[^"]*")"#,
+            r"|(?P<decimal>-?(?:(?:[0-9]+\.[0-9]*|[0-9]*\.[0-9]+)(?:[Ee][+-]?[0-9]+)?|[0-9]+[Ee][+-]?[0-9]+))",
+            r"|(?P<integer>-?(?:0[Xx][0-9A-Fa-f]+|0[0-7]*|[1-9][0-9]*))",
+            r"|(?P<identifier>_?[A-Za-z][0-9A-Za-z_-]*)",
+            r"|(?P<other>\.\.\.|[^\t\n\r 0-9A-Za-z])",
+            r")"
+        ))
+        .unwrap();
+    }
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut in_comment = false;
+
+    for line in block.lines.iter() {
+        let text = line
+            .text
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&");
+        let mut rest = text.as_str();
+
+        // the rest of a "/* ... */" comment that started on an earlier line
+        if in_comment {
+            match rest.find("*/") {
+                Some(pos) => {
+                    rest = &rest[pos + 2..];
+                    in_comment = false;
+                }
+                None => continue,
+            }
+        }
+
+        while !rest.is_empty() {
+            if rest.starts_with("/*") && !rest.contains("*/") {
+                in_comment = true;
+                break;
+            }
+            let caps = match TOKEN_REG.captures(rest) {
+                Some(caps) => caps,
+                None => die!(
+                    "Invalid IDL: unexpected character \"{}\".",
+                    rest.chars().next().unwrap();
+                    Some(line.location())
+                ),
+            };
+            let m = caps.get(0).unwrap();
+            let kind = if caps.name("space").is_some() {
+                None
+            } else if caps.name("string").is_some() {
+                Some(TokenKind::String)
+            } else if caps.name("decimal").is_some() {
+                Some(TokenKind::Decimal)
+            } else if caps.name("integer").is_some() {
+                Some(TokenKind::Integer)
+            } else if caps.name("identifier").is_some() {
+                Some(TokenKind::Identifier)
+            } else {
+                Some(TokenKind::Other)
+            };
+            if let Some(kind) = kind {
+                tokens.push(Token {
+                    kind,
+                    text: m.as_str().to_owned(),
+                    location: line.location(),
+                });
+            }
+            rest = &rest[m.end()..];
+        }
+    }
+
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    // the location reported for errors at the end of the block
+    end: Location,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_is(&self, text: &str) -> bool {
+        self.peek().is_some_and(|token| token.text == text)
+    }
+
+    fn peek_at_is(&self, offset: usize, text: &str) -> bool {
+        self.tokens
+            .get(self.pos + offset)
+            .is_some_and(|token| token.text == text)
+    }
+
+    // Consume the next token if it is the given text.
+    fn eat(&mut self, text: &str) -> bool {
+        if self.peek_is(text) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, text: &str) {
+        if !self.eat(text) {
+            self.error(&format!("\"{}\"", text));
+        }
+    }
+
+    fn error(&self, expected: &str) -> ! {
+        match self.peek() {
+            Some(token) => die!(
+                "Invalid IDL: expected {}, but found \"{}\".",
+                expected,
+                token.text;
+                Some(token.location.clone())
+            ),
+            None => die!(
+                "Invalid IDL: expected {}, but the block ended.",
+                expected;
+                Some(self.end.clone())
+            ),
+        }
+    }
+
+    fn next_of_kind(&mut self, kind: TokenKind, expected: &str) -> String {
+        match self.peek() {
+            Some(token) if token.kind == kind => {
+                let text = token.text.clone();
+                self.pos += 1;
+                text
+            }
+            _ => self.error(expected),
+        }
+    }
+
+    // An identifier, without the leading "_" that escapes keywords.
+    fn identifier(&mut self) -> String {
+        let text = self.next_of_kind(TokenKind::Identifier, "a name");
+        text.strip_prefix('_').map_or(text.clone(), str::to_owned)
+    }
+
+    fn parse_definition(&mut self) -> Definition {
+        let ext_attrs = self.parse_ext_attrs();
+
+        if self.eat("callback") {
+            if self.eat("interface") {
+                return self.parse_container("callback interface", false, ext_attrs);
+            }
+            let name = self.identifier();
+            self.expect("=");
+            let return_type = self.parse_type();
+            let arguments = self.parse_arguments();
+            self.expect(";");
+            return Definition::Callback {
+                name,
+                return_type,
+                arguments,
+                ext_attrs,
+            };
+        }
+
+        let partial = self.eat("partial");
+        if self.eat("interface") {
+            let kind = if self.eat("mixin") {
+                "interface mixin"
+            } else {
+                "interface"
+            };
+            return self.parse_container(kind, partial, ext_attrs);
+        }
+        if self.eat("dictionary") {
+            return self.parse_container("dictionary", partial, ext_attrs);
+        }
+        if self.eat("namespace") {
+            return self.parse_container("namespace", partial, ext_attrs);
+        }
+        if partial {
+            self.error("\"interface\", \"dictionary\" or \"namespace\"");
+        }
+
+        if self.eat("enum") {
+            let name = self.identifier();
+            self.expect("{");
+            let mut values: Vec<String> = Vec::new();
+            while !self.eat("}") {
+                let value = self.next_of_kind(TokenKind::String, "an enum value");
+                values.push(value.trim_matches('"').to_owned());
+                if !self.eat(",") {
+                    self.expect("}");
+                    break;
+                }
+            }
+            self.expect(";");
+            return Definition::Enum {
+                name,
+                values,
+                ext_attrs,
+            };
+        }
+        if self.eat("typedef") {
+            let idl_type = self.parse_type_with_ext_attrs();
+            let name = self.identifier();
+            self.expect(";");
+            return Definition::Typedef {
+                name,
+                idl_type,
+                ext_attrs,
+            };
+        }
+
+        let target = self.identifier();
+        self.expect("includes");
+        let includes = self.identifier();
+        self.expect(";");
+        Definition::Includes {
+            target,
+            includes,
+            ext_attrs,
+        }
+    }
+
+    fn parse_container(
+        &mut self,
+        kind: &'static str,
+        partial: bool,
+        ext_attrs: Vec<ExtAttr>,
+    ) -> Definition {
+        let name = self.identifier();
+        let inheritance = if self.eat(":") {
+            Some(self.identifier())
+        } else {
+            None
+        };
+        self.expect("{");
+        let mut members: Vec<Member> = Vec::new();
+        while !self.eat("}") {
+            members.push(self.parse_member(kind));
+        }
+        self.expect(";");
+        Definition::Container {
+            kind,
+            name,
+            partial,
+            inheritance,
+            members,
+            ext_attrs,
+        }
+    }
+
+    fn parse_member(&mut self, container_kind: &str) -> Member {
+        let ext_attrs = self.parse_ext_attrs();
+
+        if container_kind == "dictionary" {
+            let required = self.eat("required");
+            let idl_type = self.parse_type_with_ext_attrs();
+            let name = self.identifier();
+            let default = if self.eat("=") {
+                Some(self.parse_value())
+            } else {
+                None
+            };
+            self.expect(";");
+            return Member::Field {
+                name,
+                idl_type,
+                required,
+                default,
+                ext_attrs,
+            };
+        }
+
+        if self.eat("const") {
+            let idl_type = self.parse_type();
+            let name = self.identifier();
+            self.expect("=");
+            let value = self.parse_value();
+            self.expect(";");
+            return Member::Const {
+                name,
+                idl_type,
+                value,
+                ext_attrs,
+            };
+        }
+        if self.eat("constructor") {
+            let arguments = self.parse_arguments();
+            self.expect(";");
+            return Member::Constructor {
+                arguments,
+                ext_attrs,
+            };
+        }
+
+        let special = if self.eat("static") {
+            "static"
+        } else if self.eat("stringifier") {
+            if self.eat(";") {
+                return Member::Operation {
+                    name: String::new(),
+                    return_type: None,
+                    arguments: Vec::new(),
+                    special: "stringifier",
+                    ext_attrs,
+                };
+            }
+            "stringifier"
+        } else if self.eat("inherit") {
+            "inherit"
+        } else if self.eat("getter") {
+            "getter"
+        } else if self.eat("setter") {
+            "setter"
+        } else if self.eat("deleter") {
+            "deleter"
+        } else {
+            ""
+        };
+
+        let readonly = self.eat("readonly");
+        let is_async = self.eat("async");
+        let declaration = if self.eat("iterable") {
+            Some("iterable")
+        } else if self.eat("async_iterable") {
+            Some("async_iterable")
+        } else if self.eat("maplike") {
+            Some("maplike")
+        } else if self.eat("setlike") {
+            Some("setlike")
+        } else {
+            None
+        };
+        if let Some(kind) = declaration {
+            self.expect("<");
+            let mut idl_types = vec![self.parse_type_with_ext_attrs()];
+            while self.eat(",") {
+                idl_types.push(self.parse_type_with_ext_attrs());
+            }
+            self.expect(">");
+            let arguments = if self.peek_is("(") {
+                self.parse_arguments()
+            } else {
+                Vec::new()
+            };
+            self.expect(";");
+            return Member::Declaration {
+                kind: if kind == "async_iterable" {
+                    "iterable"
+                } else {
+                    kind
+                },
+                idl_types,
+                readonly,
+                is_async: is_async || kind == "async_iterable",
+                arguments,
+                ext_attrs,
+            };
+        }
+
+        if self.eat("attribute") {
+            let idl_type = self.parse_type_with_ext_attrs();
+            let name = self.identifier();
+            self.expect(";");
+            return Member::Attribute {
+                name,
+                idl_type,
+                special,
+                readonly,
+                ext_attrs,
+            };
+        }
+        if readonly || is_async {
+            self.error("\"attribute\"");
+        }
+
+        let return_type = self.parse_type();
+        let name = if self.peek_is("(") {
+            String::new()
+        } else {
+            self.identifier()
+        };
+        let arguments = self.parse_arguments();
+        self.expect(";");
+        Member::Operation {
+            name,
+            return_type: Some(return_type),
+            arguments,
+            special,
+            ext_attrs,
+        }
+    }
+
+    fn parse_arguments(&mut self) -> Vec<Argument> {
+        self.expect("(");
+        let mut arguments: Vec<Argument> = Vec::new();
+        if self.eat(")") {
+            return arguments;
+        }
+        loop {
+            let ext_attrs = self.parse_ext_attrs();
+            let optional = self.eat("optional");
+            let idl_type = if optional {
+                self.parse_type_with_ext_attrs()
+            } else {
+                self.parse_type()
+            };
+            let variadic = !optional && self.eat("...");
+            let name = self.identifier();
+            let default = if optional && self.eat("=") {
+                Some(self.parse_value())
+            } else {
+                None
+            };
+            arguments.push(Argument {
+                name,
+                idl_type,
+                optional,
+                variadic,
+                default,
+                ext_attrs,
+            });
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect(")");
+        arguments
+    }
+
+    fn parse_type_with_ext_attrs(&mut self) -> IdlType {
+        let ext_attrs = self.parse_ext_attrs();
+        let mut idl_type = self.parse_type();
+        idl_type.ext_attrs = ext_attrs;
+        idl_type
+    }
+
+    fn parse_type(&mut self) -> IdlType {
+        let mut idl_type = if self.eat("(") {
+            let mut types = vec![self.parse_type_with_ext_attrs()];
+            while self.eat("or") {
+                types.push(self.parse_type_with_ext_attrs());
+            }
+            self.expect(")");
+            if types.len() < 2 {
+                self.error("\"or\"");
+            }
+            IdlType {
+                generic: String::new(),
+                nullable: false,
+                union: true,
+                inner: TypeInner::Types(types),
+                ext_attrs: Vec::new(),
+            }
+        } else {
+            let name = self.parse_type_name();
+            if GENERIC_TYPES.contains(&name.as_str()) {
+                self.expect("<");
+                let mut types = vec![self.parse_type_with_ext_attrs()];
+                while self.eat(",") {
+                    types.push(self.parse_type_with_ext_attrs());
+                }
+                self.expect(">");
+                IdlType {
+                    generic: name,
+                    nullable: false,
+                    union: false,
+                    inner: TypeInner::Types(types),
+                    ext_attrs: Vec::new(),
+                }
+            } else {
+                IdlType {
+                    generic: String::new(),
+                    nullable: false,
+                    union: false,
+                    inner: TypeInner::Name(name),
+                    ext_attrs: Vec::new(),
+                }
+            }
+        };
+        idl_type.nullable = self.eat("?");
+        idl_type
+    }
+
+    // The name of a type, which can be several words (e.g. "unsigned long long").
+    fn parse_type_name(&mut self) -> String {
+        let mut words: Vec<String> = Vec::new();
+        if self.eat("unsigned") {
+            words.push("unsigned".to_owned());
+        } else if self.eat("unrestricted") {
+            words.push("unrestricted".to_owned());
+        }
+        let name = self.identifier();
+        let is_long = name == "long";
+        words.push(name);
+        if is_long && self.eat("long") {
+            words.push("long".to_owned());
+        }
+        words.join(" ")
+    }
+
+    fn parse_value(&mut self) -> Value {
+        let token = match self.peek() {
+            Some(token) => token,
+            None => self.error("a value"),
+        };
+        let value = match token.kind {
+            TokenKind::String => Value::String(token.text.trim_matches('"').to_owned()),
+            TokenKind::Integer | TokenKind::Decimal => Value::Number(token.text.clone()),
+            _ => match token.text.as_str() {
+                "true" => Value::Boolean(true),
+                "false" => Value::Boolean(false),
+                "null" => Value::Null,
+                "Infinity" => Value::Infinity(false),
+                "NaN" => Value::NaN,
+                "-" if self.peek_at_is(1, "Infinity") => {
+                    self.pos += 1;
+                    Value::Infinity(true)
+                }
+                "[" if self.peek_at_is(1, "]") => {
+                    self.pos += 1;
+                    Value::Sequence
+                }
+                "{" if self.peek_at_is(1, "}") => {
+                    self.pos += 1;
+                    Value::Dictionary
+                }
+                _ => self.error("a value"),
+            },
+        };
+        self.pos += 1;
+        value
+    }
+
+    // Parse a list of extended attributes (e.g. "[Exposed=Window, SameObject]"), if any.
+    fn parse_ext_attrs(&mut self) -> Vec<ExtAttr> {
+        let mut ext_attrs: Vec<ExtAttr> = Vec::new();
+        if !self.eat("[") {
+            return ext_attrs;
+        }
+        loop {
+            let name = self.identifier();
+            let mut rhs = None;
+            let mut arguments = Vec::new();
+            if self.eat("=") {
+                rhs = Some(if self.eat("(") {
+                    let mut values = vec![self.identifier()];
+                    while self.eat(",") {
+                        values.push(self.identifier());
+                    }
+                    self.expect(")");
+                    ("identifier-list", values)
+                } else if self.eat("*") {
+                    ("*", vec!["*".to_owned()])
+                } else {
+                    match self.peek().map(|token| token.kind) {
+                        Some(TokenKind::String) => (
+                            "string",
+                            vec![self.next_of_kind(TokenKind::String, "a string")],
+                        ),
+                        Some(TokenKind::Integer) => (
+                            "integer",
+                            vec![self.next_of_kind(TokenKind::Integer, "an integer")],
+                        ),
+                        Some(TokenKind::Decimal) => (
+                            "decimal",
+                            vec![self.next_of_kind(TokenKind::Decimal, "a decimal")],
+                        ),
+                        _ => ("identifier", vec![self.identifier()]),
+                    }
+                });
+            }
+            if self.peek_is("(") {
+                arguments = self.parse_arguments();
+            }
+            ext_attrs.push(ExtAttr {
+                name,
+                rhs,
+                arguments,
+            });
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect("]");
+        ext_attrs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::Line;
+    use std::collections::BTreeMap;
+
+    fn parse(text: &str) -> Vec<Definition> {
+        let block = Block {
+            block_type: "idl",
+            attrs: BTreeMap::new(),
+            lines: text
+                .lines()
+                .enumerate()
+                .map(|(i, text)| Line {
+                    index: i as u32 + 1,
+                    text: text.to_owned(),
+                    file: None,
+                })
+                .collect(),
+            location: Location::default(),
+        };
+        parse_block(&block)
+    }
+
+    fn members_of(definition: &Definition) -> &[Member] {
+        match definition {
+            Definition::Container { members, .. } => members,
+            _ => panic!("not a container: {:?}", definition),
+        }
+    }
+
+    #[test]
+    fn test_parse_interface() {
+        let definitions = parse(
+            "[Exposed=Window]
+            interface Foo : Bar {
+              constructor(long a);
+              readonly attribute DOMString? name;
+              static Promise<undefined> bar(optional (long or DOMString) x = 1, long... rest);
+              const unsigned long long MAX = 0xFF;
+              stringifier;
+              iterable<long>;
+            };",
+        );
+        assert_eq!(definitions.len(), 1);
+        match &definitions[0] {
+            Definition::Container {
+                kind,
+                name,
+                partial,
+                inheritance,
+                ext_attrs,
+                ..
+            } => {
+                assert_eq!(*kind, "interface");
+                assert_eq!(name, "Foo");
+                assert!(!partial);
+                assert_eq!(inheritance.as_deref(), Some("Bar"));
+                assert_eq!(ext_attrs[0].name, "Exposed");
+                assert_eq!(
+                    ext_attrs[0].rhs,
+                    Some(("identifier", vec!["Window".to_owned()]))
+                );
+            }
+            definition => panic!("not an interface: {:?}", definition),
+        }
+
+        let members = members_of(&definitions[0]);
+        assert_eq!(members.len(), 6);
+        match &members[0] {
+            Member::Constructor { arguments, .. } => assert_eq!(arguments[0].name, "a"),
+            member => panic!("not a constructor: {:?}", member),
+        }
+        match &members[1] {
+            Member::Attribute {
+                name,
+                idl_type,
+                readonly,
+                ..
+            } => {
+                assert_eq!(name, "name");
+                assert_eq!(idl_type.to_string(), "DOMString?");
+                assert!(readonly);
+            }
+            member => panic!("not an attribute: {:?}", member),
+        }
+        match &members[2] {
+            Member::Operation {
+                name,
+                return_type,
+                arguments,
+                special,
+                ..
+            } => {
+                assert_eq!(name, "bar");
+                assert_eq!(*special, "static");
+                assert_eq!(
+                    return_type.as_ref().unwrap().to_string(),
+                    "Promise<undefined>"
+                );
+                assert_eq!(arguments[0].idl_type.to_string(), "(long or DOMString)");
+                assert!(arguments[0].optional);
+                assert!(matches!(arguments[0].default, Some(Value::Number(ref n)) if n == "1"));
+                assert!(arguments[1].variadic);
+            }
+            member => panic!("not an operation: {:?}", member),
+        }
+        match &members[3] {
+            Member::Const {
+                idl_type, value, ..
+            } => {
+                assert_eq!(idl_type.to_string(), "unsigned long long");
+                assert!(matches!(value, Value::Number(n) if n == "0xFF"));
+            }
+            member => panic!("not a const: {:?}", member),
+        }
+        assert!(matches!(
+            &members[4],
+            Member::Operation {
+                special: "stringifier",
+                return_type: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &members[5],
+            Member::Declaration {
+                kind: "iterable",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_dictionary() {
+        let definitions = parse(
+            "partial dictionary Options {
+              required long a;
+              sequence<DOMString> b = [];
+              boolean c = false;
+            };",
+        );
+        assert!(matches!(
+            &definitions[0],
+            Definition::Container {
+                kind: "dictionary",
+                partial: true,
+                ..
+            }
+        ));
+        let members = members_of(&definitions[0]);
+        assert!(matches!(
+            &members[0],
+            Member::Field {
+                required: true,
+                default: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &members[1],
+            Member::Field {
+                default: Some(Value::Sequence),
+                ..
+            }
+        ));
+        assert!(matches!(
+            &members[2],
+            Member::Field {
+                default: Some(Value::Boolean(false)),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_other_definitions() {
+        let definitions = parse(
+            r#"enum Mode { "a", "b", };
+            typedef (Node or record<DOMString, long>)? Thing;
+            callback Handler = undefined (Event e);
+            Foo includes Mixin;"#,
+        );
+        assert_eq!(definitions.len(), 4);
+        match &definitions[0] {
+            Definition::Enum { values, .. } => assert_eq!(values, &["a", "b"]),
+            definition => panic!("not an enum: {:?}", definition),
+        }
+        match &definitions[1] {
+            Definition::Typedef { idl_type, .. } => {
+                assert_eq!(idl_type.to_string(), "(Node or record<DOMString, long>)?")
+            }
+            definition => panic!("not a typedef: {:?}", definition),
+        }
+        assert!(matches!(&definitions[2], Definition::Callback { name, .. } if name == "Handler"));
+        assert!(matches!(
+            &definitions[3],
+            Definition::Includes { target, includes, .. } if target == "Foo" && includes == "Mixin"
+        ));
+    }
+
+    #[test]
+    fn test_parse_comments_and_escapes() {
+        let definitions = parse(
+            "/* a comment
+              on two lines */
+            interface Foo { // another comment
+              attribute sequence&lt;long&gt; bar;
+            };",
+        );
+        match &members_of(&definitions[0])[0] {
+            Member::Attribute { idl_type, .. } => {
+                assert_eq!(idl_type.to_string(), "sequence<long>")
+            }
+            member => panic!("not an attribute: {:?}", member),
+        }
+    }
+}
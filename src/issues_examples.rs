@@ -0,0 +1,285 @@
+use kuchiki::iter::NodeIterator;
+use kuchiki::NodeRef;
+use std::collections::HashSet;
+
+use crate::html;
+use crate::spec::Spec;
+
+// Turn paragraphs starting with "Note:", "Issue:" or "Advisement:" into classed paragraphs.
+// The "Issue:" and "Advisement:" prefixes are dropped, since the styles mark those
+// paragraphs already.
+pub fn process_paragraph_prefixes(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let paragraphs: Vec<NodeRef> = document
+        .select("p:not(.note):not(.issue):not(.advisement)")
+        .unwrap()
+        .map(|p| p.as_node().clone())
+        .collect();
+
+    for p in paragraphs.iter() {
+        let text = p.text_contents();
+        let text = text.trim_start();
+        if text.starts_with("Note: ") || text.starts_with("Note, ") {
+            html::node::add_class(p, "note");
+            html::node::set_attr(p, "role", "note".to_owned());
+        } else if text.starts_with("Issue: ") {
+            html::node::add_class(p, "issue");
+            strip_prefix(p, "Issue:");
+        } else if text.starts_with("Advisement: ") {
+            html::node::add_class(p, "advisement");
+            strip_prefix(p, "Advisement:");
+        }
+    }
+}
+
+fn strip_prefix(el: &NodeRef, prefix: &str) {
+    let text_node = el
+        .descendants()
+        .text_nodes()
+        .find(|text| !text.borrow().trim().is_empty());
+    if let Some(text_node) = text_node {
+        let new_text = match text_node.borrow().trim_start().strip_prefix(prefix) {
+            Some(rest) => rest.trim_start().to_owned(),
+            None => return,
+        };
+        *text_node.borrow_mut() = new_text;
+    }
+}
+
+// Give notes, issues and advisements with a "heading" attribute a marker heading.
+pub fn add_note_headings(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let els: Vec<NodeRef> = document
+        .select(".note[heading], .issue[heading], .advisement[heading]")
+        .unwrap()
+        .map(|el| el.as_node().clone())
+        .collect();
+
+    for el in els.iter() {
+        let kind = if html::node::has_class(el, "note") {
+            "Note"
+        } else if html::node::has_class(el, "issue") {
+            "Issue"
+        } else {
+            "Advisement"
+        };
+        let heading = html::node::remove_attr(el, "heading").unwrap_or_default();
+        html::node::add_class(el, "has-heading");
+        el.prepend(html::node::new_text_element(
+            "div",
+            btreemap! {
+                "class" => "marker".to_owned(),
+            },
+            &format!("{}: {}", kind, heading),
+        ));
+    }
+    for note in document.select(".note:not([role])").unwrap() {
+        html::node::set_attr(note.as_node(), "role", "note".to_owned());
+    }
+}
+
+// Give examples stable ids, and "Example N" headings with self-links.
+pub fn process_examples(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let examples: Vec<NodeRef> = document
+        .select(".example")
+        .unwrap()
+        .map(|el| el.as_node().clone())
+        .collect();
+
+    let mut used_ids: HashSet<String> = HashSet::new();
+    let mut number = 0;
+
+    for example in examples.iter() {
+        // a code block is wrapped, so that the heading is not part of the code
+        let example = if html::node::is_element(example, "pre") {
+            let wrapper = html::node::new_element("div", None);
+            for class in ["example", "invalid", "illegal", "no-marker"].iter() {
+                if html::node::has_class(example, class) {
+                    html::node::remove_class(example, class);
+                    html::node::add_class(&wrapper, class);
+                }
+            }
+            if let Some(id) = html::node::remove_attr(example, "id") {
+                html::node::set_attr(&wrapper, "id", id);
+            }
+            if let Some(title) = html::node::remove_attr(example, "title") {
+                html::node::set_attr(&wrapper, "title", title);
+            }
+            example.insert_before(wrapper.clone());
+            wrapper.append(example.clone());
+            wrapper
+        } else {
+            example.clone()
+        };
+
+        let id = ensure_hash_id(&example, "example", &example.text_contents(), &mut used_ids);
+
+        if html::node::has_class(&example, "no-marker") {
+            continue;
+        }
+
+        number += 1;
+        let kind = if html::node::has_class(&example, "invalid")
+            || html::node::has_class(&example, "illegal")
+        {
+            "Invalid Example"
+        } else {
+            "Example"
+        };
+        let heading = match html::node::remove_attr(&example, "title") {
+            Some(title) => format!("{} {}: {}", kind, number, title),
+            None => format!("{} {}", kind, number),
+        };
+
+        example.prepend(html::node::new_text_element(
+            "div",
+            btreemap! {
+                "class" => "marker".to_owned(),
+            },
+            &heading,
+        ));
+        example.prepend(html::node::new_element(
+            "a",
+            btreemap! {
+                "class" => "self-link".to_owned(),
+                "href" => format!("#{}", id),
+            },
+        ));
+    }
+}
+
+// Give issues stable ids, so that the issues index can link back to them.
+pub fn process_issues(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let mut used_ids: HashSet<String> = HashSet::new();
+    for issue in document.select(".issue").unwrap() {
+        let issue = issue.as_node();
+        ensure_hash_id(issue, "issue", &issue.text_contents(), &mut used_ids);
+    }
+}
+
+// Return the id of the element, or give it one like "example-1a2b3c4d" based on the text.
+pub fn ensure_hash_id(
+    el: &NodeRef,
+    prefix: &str,
+    text: &str,
+    used_ids: &mut HashSet<String>,
+) -> String {
+    if let Some(id) = html::node::get_attr(el, "id") {
+        used_ids.insert(id.clone());
+        return id;
+    }
+
+    let hash = format!("{}-{}", prefix, hash_contents(text));
+    let mut id = hash.clone();
+    let mut suffix = 0;
+    while used_ids.contains(&id) {
+        id = format!("{}-{}", hash, suffix);
+        suffix += 1;
+    }
+    html::node::set_attr(el, "id", id.clone());
+    used_ids.insert(id.clone());
+    id
+}
+
+// A short hash of the text, which stays the same as long as the text does (FNV-1a).
+fn hash_contents(text: &str) -> String {
+    let hash = text.trim().bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    format!("{:08x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+
+    fn new_spec(html: &str) -> Spec<'static> {
+        let mut doc = Spec::default();
+        doc.document = Some(kuchiki::parse_html().one(html));
+        doc
+    }
+
+    fn body_of(doc: &Spec) -> String {
+        let body = doc.document.as_ref().unwrap().select_first("body").unwrap();
+        body.as_node()
+            .children()
+            .map(|child| child.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_hash_contents() {
+        assert_eq!(hash_contents(""), "811c9dc5");
+        assert_eq!(hash_contents(" a "), hash_contents("a"));
+        assert_ne!(hash_contents("a"), hash_contents("b"));
+    }
+
+    #[test]
+    fn test_ensure_hash_id() {
+        let doc = new_spec("<p id=kept>a</p><p>b</p><p>b</p>");
+        let ps: Vec<NodeRef> = doc
+            .document
+            .as_ref()
+            .unwrap()
+            .select("p")
+            .unwrap()
+            .map(|p| p.as_node().clone())
+            .collect();
+        let mut used_ids = HashSet::new();
+        let hash = format!("example-{}", hash_contents("b"));
+        assert_eq!(
+            ensure_hash_id(&ps[0], "example", "a", &mut used_ids),
+            "kept"
+        );
+        assert_eq!(ensure_hash_id(&ps[1], "example", "b", &mut used_ids), hash);
+        assert_eq!(
+            ensure_hash_id(&ps[2], "example", "b", &mut used_ids),
+            format!("{}-0", hash)
+        );
+        assert_eq!(
+            html::node::get_attr(&ps[2], "id"),
+            Some(format!("{}-0", hash))
+        );
+    }
+
+    #[test]
+    fn test_process_paragraph_prefixes() {
+        let mut doc =
+            new_spec("<p>Note: a</p><p> Issue: <em>b</em></p><p>Advisement: c</p><p>Notes: d</p>");
+        process_paragraph_prefixes(&mut doc);
+        assert_eq!(
+            body_of(&doc),
+            "<p class=\"note\" role=\"note\">Note: a</p>\
+             <p class=\"issue\"><em>b</em></p>\
+             <p class=\"advisement\">c</p>\
+             <p>Notes: d</p>"
+        );
+    }
+
+    #[test]
+    fn test_process_examples() {
+        let mut doc = new_spec(
+            "<div class=example id=first>a</div>\
+             <pre class='example invalid' title=Bad>b</pre>\
+             <div class='example no-marker' id=last>c</div>",
+        );
+        process_examples(&mut doc);
+        let id = format!("example-{}", hash_contents("b"));
+        assert_eq!(
+            body_of(&doc),
+            format!(
+                "<div class=\"example\" id=\"first\">\
+                 <a class=\"self-link\" href=\"#first\"></a>\
+                 <div class=\"marker\">Example 1</div>a</div>\
+                 <div class=\"example invalid\" id=\"{0}\">\
+                 <a class=\"self-link\" href=\"#{0}\"></a>\
+                 <div class=\"marker\">Invalid Example 2: Bad</div><pre>b</pre></div>\
+                 <div class=\"example no-marker\" id=\"last\">c</div>",
+                id
+            )
+        );
+    }
+}
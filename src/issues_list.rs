@@ -0,0 +1,334 @@
+use regex::Regex;
+use std::fs;
+
+use crate::html;
+use crate::util::regex::PAIR_REG;
+
+// Color codes of issue statuses.
+const STATUS_LEGEND: [(&str, &str); 5] = [
+    ("a", "Accepted or Rejected and positive response"),
+    ("r", "Rejected and no response"),
+    ("fo", "Rejected and negative response"),
+    ("d", "Deferred"),
+    ("oi", "Open issue"),
+];
+
+// The fields of an issue. Other lines (e.g. a URL on a line of its own) continue the
+// previous field.
+const ISSUE_FIELDS: [&str; 10] = [
+    "Summary",
+    "From",
+    "Comment",
+    "Response",
+    "Closed",
+    "Verified",
+    "Resolved",
+    "Objection",
+    "Note",
+    "Open",
+];
+
+#[derive(Debug, Default)]
+struct HeaderInfo {
+    title: String,
+    url: String,
+    ed: Option<String>,
+    date: Option<String>,
+    status: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Issue {
+    number: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Issue {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, val)| val.as_str())
+    }
+
+    fn status_code(&self) -> &'static str {
+        let closed = self.get("Closed").unwrap_or("").to_lowercase();
+        if self.get("Objection").is_some() {
+            "fo"
+        } else if self.get("Verified").is_some() {
+            "a"
+        } else if closed.starts_with("rejected") {
+            "r"
+        } else if closed.starts_with("deferred") {
+            "d"
+        } else if !closed.is_empty() {
+            "a"
+        } else {
+            "oi"
+        }
+    }
+}
+
+// Turn a plain-text issues file (in the disposition-of-comments format) into an HTML table.
+pub fn print_issues_list(infile: &str, outfile: Option<&str>) {
+    let text = match fs::read_to_string(infile) {
+        Ok(text) => text,
+        Err(_) => die!("Couldn't read the issues file \"{}\".", infile),
+    };
+
+    let mut chunks = text.split("\n----\n");
+    let header_info = parse_header_info(chunks.next().unwrap_or(""));
+    let issues: Vec<Issue> = chunks
+        .filter(|chunk| !chunk.trim().is_empty())
+        .map(parse_issue)
+        .collect();
+
+    let outfile = match outfile {
+        Some(outfile) => outfile.to_owned(),
+        None => format!("{}.html", infile.trim_end_matches(".txt")),
+    };
+    let rendered = render_issues_list(&header_info, &issues);
+    fs::write(outfile, rendered).expect("unable to write file");
+}
+
+fn parse_header_info(text: &str) -> HeaderInfo {
+    lazy_static! {
+        // draft url reg (e.g. "https://www.w3.org/TR/2013/WD-css-foo-3-20130101/")
+        static ref DRAFT_URL_REG: Regex =
+            Regex::new(r"/([A-Z]{2,})-[a-z0-9-]+-(\d{4})(\d{2})(\d{2})/?$").unwrap();
+    }
+
+    let mut header_info = HeaderInfo::default();
+    let mut has_title = false;
+    let mut has_url = false;
+
+    for line in text.lines() {
+        let caps = match PAIR_REG.captures(line) {
+            Some(caps) => caps,
+            None => continue,
+        };
+        let val = caps[2].trim().to_owned();
+        match caps[1].trim() {
+            "Draft" => {
+                header_info.url = val;
+                has_url = true;
+            }
+            "Title" => {
+                header_info.title = val;
+                has_title = true;
+            }
+            "ED" => header_info.ed = Some(val),
+            "Date" => header_info.date = Some(val),
+            "Status" => header_info.status = Some(val),
+            _ => {}
+        }
+    }
+
+    if !has_url {
+        die!("Missing \"Draft\" metadata in the issues file.");
+    }
+    if !has_title {
+        die!("Missing \"Title\" metadata in the issues file.");
+    }
+
+    // the status and date of a dated draft can be read from its url
+    if let Some(caps) = DRAFT_URL_REG.captures(&header_info.url) {
+        if header_info.status.is_none() {
+            header_info.status = Some(caps[1].to_owned());
+        }
+        if header_info.date.is_none() {
+            header_info.date = Some(format!("{}-{}-{}", &caps[2], &caps[3], &caps[4]));
+        }
+    }
+
+    header_info
+}
+
+fn parse_issue(text: &str) -> Issue {
+    lazy_static! {
+        // issue number reg
+        static ref NUMBER_REG: Regex = Regex::new(r"^Issue (\d+)\.?\s*$").unwrap();
+    }
+
+    let mut lines = text.trim().lines();
+    let first_line = lines.next().unwrap_or("");
+    let number = match NUMBER_REG.captures(first_line.trim()) {
+        Some(caps) => caps[1].to_owned(),
+        None => die!(
+            "Issues must start with a line like \"Issue 1.\", but found \"{}\".",
+            first_line
+        ),
+    };
+
+    let mut issue = Issue {
+        number,
+        ..Default::default()
+    };
+    for line in lines {
+        let field = PAIR_REG
+            .captures(line)
+            .filter(|caps| ISSUE_FIELDS.contains(&caps[1].trim()));
+        match field {
+            Some(caps) if !line.starts_with(char::is_whitespace) => {
+                issue
+                    .fields
+                    .push((caps[1].trim().to_owned(), caps[2].trim().to_owned()));
+            }
+            _ => {
+                if let Some(last) = issue.fields.last_mut() {
+                    last.1.push('\n');
+                    last.1.push_str(line.trim());
+                }
+            }
+        }
+    }
+    issue
+}
+
+fn render_issues_list(header_info: &HeaderInfo, issues: &[Issue]) -> String {
+    let title = format!(
+        "{} Disposition of Comments{}{}",
+        header_info.title,
+        header_info
+            .date
+            .as_ref()
+            .map_or(String::new(), |date| format!(" for {}", date)),
+        header_info
+            .status
+            .as_ref()
+            .map_or(String::new(), |status| format!(" {}", status)),
+    );
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>{}</title>\n",
+        html::helper::escape_html(&title)
+    ));
+    html.push_str(&format!(
+        "<style>\n{}\n</style>\n",
+        include_str!("style/issues-list.css")
+    ));
+    html.push_str(&format!("<h1>{}</h1>\n", html::helper::escape_html(&title)));
+    html.push_str(&format!(
+        "<p>Review document: {}\n",
+        linkify(&header_info.url)
+    ));
+    if let Some(ref ed) = header_info.ed {
+        html.push_str(&format!("<p>Editor's draft: {}\n", linkify(ed)));
+    }
+
+    html.push_str("<p>The following color coding convention is used for comments:\n<ul>\n");
+    for (code, description) in STATUS_LEGEND.iter() {
+        html.push_str(&format!("<li class=\"{}\">{}\n", code, description));
+    }
+    html.push_str("</ul>\n");
+
+    let columns = ["Summary", "From", "Comment", "Response", "Closed"];
+    html.push_str("<table>\n<thead><tr><th>Issue");
+    for column in columns.iter() {
+        html.push_str(&format!("<th>{}", column));
+    }
+    html.push_str("<th>Notes</thead>\n<tbody>\n");
+
+    for issue in issues.iter() {
+        html.push_str(&format!(
+            "<tr class=\"{0}\" id=\"issue-{1}\"><th><a href=\"#issue-{1}\">{1}</a>",
+            issue.status_code(),
+            issue.number
+        ));
+        for column in columns.iter() {
+            html.push_str(&format!("<td>{}", linkify(issue.get(column).unwrap_or(""))));
+        }
+        let notes: Vec<String> = issue
+            .fields
+            .iter()
+            .filter(|(key, _)| !columns.contains(&key.as_str()))
+            .map(|(key, val)| format!("{}: {}", html::helper::escape_html(key), linkify(val)))
+            .collect();
+        html.push_str(&format!("<td>{}\n", notes.join("<br>")));
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    html
+}
+
+// Escape the text, and turn the urls in it into links.
+fn linkify(text: &str) -> String {
+    lazy_static! {
+        static ref URL_REG: Regex = Regex::new(r"https?://[^\s<>]+").unwrap();
+    }
+    let text = html::helper::escape_html(text);
+    URL_REG
+        .replace_all(&text, "<a href=\"$0\">$0</a>")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header_info() {
+        let header_info = parse_header_info(
+            "Draft: https://www.w3.org/TR/2013/WD-css-foo-3-20130101/\nTitle: CSS Foo\n",
+        );
+        assert_eq!(header_info.title, "CSS Foo");
+        assert_eq!(header_info.status.as_deref(), Some("WD"));
+        assert_eq!(header_info.date.as_deref(), Some("2013-01-01"));
+    }
+
+    #[test]
+    fn test_parse_issue() {
+        let issue = parse_issue(
+            "Issue 3.\nSummary: Foo is broken\nFrom: A\nComment: https://example.org/1\n\
+             https://example.org/2\nResponse: See: the spec\nClosed: Rejected\n",
+        );
+        assert_eq!(issue.number, "3");
+        assert_eq!(
+            issue.fields,
+            vec![
+                ("Summary".to_owned(), "Foo is broken".to_owned()),
+                ("From".to_owned(), "A".to_owned()),
+                (
+                    "Comment".to_owned(),
+                    "https://example.org/1\nhttps://example.org/2".to_owned()
+                ),
+                ("Response".to_owned(), "See: the spec".to_owned()),
+                ("Closed".to_owned(), "Rejected".to_owned()),
+            ]
+        );
+        assert_eq!(issue.status_code(), "r");
+    }
+
+    #[test]
+    fn test_parse_issue_continues_indented_keys() {
+        let issue = parse_issue("Issue 1.\nSummary: Foo\n  Note: not a field\n");
+        assert_eq!(
+            issue.fields,
+            vec![("Summary".to_owned(), "Foo\nNote: not a field".to_owned())]
+        );
+        assert_eq!(issue.status_code(), "oi");
+    }
+
+    #[test]
+    fn test_status_code() {
+        let issue = |fields: &[(&str, &str)]| Issue {
+            number: "1".to_owned(),
+            fields: fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+        assert_eq!(issue(&[("Closed", "Accepted")]).status_code(), "a");
+        assert_eq!(issue(&[("Closed", "Deferred")]).status_code(), "d");
+        assert_eq!(
+            issue(&[("Closed", "Rejected"), ("Verified", "A")]).status_code(),
+            "a"
+        );
+        assert_eq!(
+            issue(&[("Closed", "Rejected"), ("Objection", "A")]).status_code(),
+            "fo"
+        );
+    }
+}
@@ -1,5 +1,42 @@
-#[derive(Debug, Clone)]
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Default)]
 pub struct Line {
     pub index: u32,
     pub text: String,
+    // the included file that the line comes from (none for the main source file)
+    pub file: Option<Rc<str>>,
+}
+
+impl Line {
+    pub fn location(&self) -> Location {
+        Location {
+            index: self.index,
+            file: self.file.clone(),
+        }
+    }
+
+    // Make a line at the same location with different text.
+    pub fn with_text(&self, text: String) -> Line {
+        Line {
+            text,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Location {
+    pub index: u32,
+    pub file: Option<Rc<str>>,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.file {
+            Some(ref file) => write!(fmt, "{}:{}", file, self.index),
+            None => write!(fmt, "Line {}", self.index),
+        }
+    }
 }
@@ -0,0 +1,205 @@
+use kuchiki::NodeRef;
+use std::collections::HashSet;
+
+use crate::anchors::Anchor;
+use crate::html;
+use crate::spec::Spec;
+
+// Link types that can be resolved by a definition of another type.
+fn dfn_types_for(link_type: &str) -> Vec<&str> {
+    match link_type {
+        "propdesc" => vec!["property", "descriptor"],
+        "maybe" => vec!["value", "type", "function", "property", "descriptor"],
+        _ => vec![link_type],
+    }
+}
+
+// Give an id to each definition without one, before the indexes copy or link to them.
+// Definitions with the same text get ids like "dfn-foo-0".
+pub fn add_dfn_ids(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let mut used_ids: HashSet<String> = document
+        .select("[id]")
+        .unwrap()
+        .filter_map(|el| html::node::get_attr(el.as_node(), "id"))
+        .collect();
+
+    for dfn in document.select("dfn").unwrap() {
+        let dfn = dfn.as_node();
+        if html::node::get_attr(dfn, "id").is_some() {
+            continue;
+        }
+        let base = dfn_id(dfn);
+        let mut id = base.clone();
+        let mut suffix = 0;
+        while used_ids.contains(&id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        html::node::set_attr(dfn, "id", id.clone());
+        used_ids.insert(id);
+    }
+}
+
+// Resolve the autolinks (e.g. "<a data-link-type=property>width</a>") against the
+// definitions in this spec, and then against the anchors of other specs.
+pub fn process_autolinks(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    let mut external_refs: Vec<Anchor> = Vec::new();
+
+    let dfns: Vec<NodeRef> = document
+        .select("dfn")
+        .unwrap()
+        .map(|dfn| dfn.as_node().clone())
+        .collect();
+
+    for link in document.select("a[data-link-type]").unwrap() {
+        let link = link.as_node();
+        if html::node::get_attr(link, "href").is_some() {
+            continue;
+        }
+
+        let link_type = html::node::get_attr(link, "data-link-type").unwrap();
+        let link_text = link_text(link);
+        let link_for = html::node::get_attr(link, "data-link-for");
+        let dfn_types = dfn_types_for(&link_type);
+
+        let candidates: Vec<&NodeRef> = dfns
+            .iter()
+            .filter(|dfn| dfn_types.contains(&dfn_type(dfn).as_str()) && dfn_text(dfn) == link_text)
+            .collect();
+
+        // A link with a "for" value needs a definition for the same thing, while a link
+        // without one prefers a definition without one.
+        let dfn = match link_for {
+            Some(ref link_for) => candidates.iter().find(|dfn| {
+                html::node::get_attr(dfn, "data-dfn-for")
+                    .is_some_and(|dfn_for| dfn_for.split(',').any(|val| val.trim() == link_for))
+            }),
+            None => candidates
+                .iter()
+                .find(|dfn| html::node::get_attr(dfn, "data-dfn-for").is_none())
+                .or_else(|| candidates.first()),
+        };
+
+        if let Some(dfn) = dfn {
+            let id = html::node::get_attr(dfn, "id").unwrap();
+            html::node::set_attr(link, "href", format!("#{}", id));
+        } else if let Some(anchor) =
+            find_anchor(&doc.anchors, &dfn_types, &link_text, link_for.as_deref())
+        {
+            html::node::set_attr(link, "href", anchor.url.clone());
+            if !external_refs.contains(anchor) {
+                external_refs.push(anchor.clone());
+            }
+        }
+    }
+
+    doc.external_refs = external_refs;
+}
+
+// Find the anchor of another spec that a link resolves to, with the same preference for
+// "for" values as the definitions of this spec.
+fn find_anchor<'a>(
+    anchors: &'a [Anchor],
+    dfn_types: &[&str],
+    link_text: &str,
+    link_for: Option<&str>,
+) -> Option<&'a Anchor> {
+    let mut candidates = anchors.iter().filter(|anchor| {
+        dfn_types.contains(&anchor.link_type.as_str()) && anchor.text == link_text
+    });
+    match link_for {
+        Some(link_for) => candidates.find(|anchor| anchor.link_for.iter().any(|f| f == link_for)),
+        None => {
+            let candidates: Vec<&Anchor> = candidates.collect();
+            candidates
+                .iter()
+                .find(|anchor| anchor.link_for.is_empty())
+                .or_else(|| candidates.first())
+                .cloned()
+        }
+    }
+}
+
+fn link_text(link: &NodeRef) -> String {
+    html::node::get_attr(link, "data-lt").unwrap_or_else(|| link.text_contents().trim().to_owned())
+}
+
+pub fn dfn_type(dfn: &NodeRef) -> String {
+    html::node::get_attr(dfn, "data-dfn-type").unwrap_or_else(|| "dfn".to_owned())
+}
+
+pub fn dfn_text(dfn: &NodeRef) -> String {
+    html::node::get_attr(dfn, "data-lt").unwrap_or_else(|| dfn.text_contents().trim().to_owned())
+}
+
+// Generate an id like "valdef-width-auto" for a definition without one.
+fn dfn_id(dfn: &NodeRef) -> String {
+    let prefix = match dfn_type(dfn).as_str() {
+        "property" => "propdef",
+        "descriptor" => "descdef",
+        "value" => "valdef",
+        "type" => "typedef",
+        "function" => "funcdef",
+        "element" => "elementdef",
+        "element-attr" => "element-attrdef",
+        _ => "dfn",
+    };
+    let dfn_for = html::node::get_attr(dfn, "data-dfn-for").unwrap_or_default();
+    format!("{} {} {}", prefix, dfn_for, dfn_text(dfn))
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+
+    fn new_anchor(text: &str, link_type: &str, link_for: &[&str]) -> Anchor {
+        Anchor {
+            text: text.to_owned(),
+            link_type: link_type.to_owned(),
+            spec: "A".to_owned(),
+            url: format!("https://a.example/#{}-{}", link_for.join("-"), text),
+            link_for: link_for.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_dfn_id() {
+        let document = kuchiki::parse_html()
+            .one("<dfn data-dfn-type=value data-dfn-for=width>auto</dfn><dfn>Some Term</dfn>");
+        let dfns: Vec<NodeRef> = document
+            .select("dfn")
+            .unwrap()
+            .map(|dfn| dfn.as_node().clone())
+            .collect();
+        assert_eq!(dfn_id(&dfns[0]), "valdef-width-auto");
+        assert_eq!(dfn_id(&dfns[1]), "dfn-some-term");
+    }
+
+    #[test]
+    fn test_find_anchor() {
+        let anchors = vec![
+            new_anchor("auto", "value", &["height"]),
+            new_anchor("auto", "value", &[]),
+            new_anchor("auto", "value", &["width"]),
+        ];
+        let dfn_types = dfn_types_for("maybe");
+        assert_eq!(
+            find_anchor(&anchors, &dfn_types, "auto", None),
+            Some(&anchors[1])
+        );
+        assert_eq!(
+            find_anchor(&anchors, &dfn_types, "auto", Some("width")),
+            Some(&anchors[2])
+        );
+        assert_eq!(find_anchor(&anchors, &dfn_types, "auto", Some("top")), None);
+        assert_eq!(find_anchor(&anchors, &["type"], "auto", None), None);
+    }
+}
@@ -7,12 +7,23 @@ extern crate maplit;
 
 #[macro_use]
 mod util;
+mod anchors;
+mod assertions;
 mod boilerplate;
 mod client;
 mod config;
+mod datablock;
+mod highlight;
 mod html;
+mod idl;
+mod issues_examples;
+mod issues_list;
 mod line;
+mod link;
 mod metadata;
+mod railroad;
+mod rfc2119;
+mod shorthand;
 mod spec;
 
 fn main() {
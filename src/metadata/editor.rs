@@ -0,0 +1,31 @@
+// An editor of the spec, parsed from a line like
+// "Tab Atkins Jr., w3cid 42199, Google, http://www.google.com/, http://xanthir.com/contact/".
+#[derive(Debug, Clone, Default)]
+pub struct Editor {
+    pub name: String,
+    pub w3c_id: Option<String>,
+    pub org: Option<String>,
+    pub org_link: Option<String>,
+    pub email: Option<String>,
+    pub link: Option<String>,
+    pub note: Option<String>,
+}
+
+impl Editor {
+    pub fn fields_mut(&mut self) -> Vec<&mut String> {
+        let mut fields = vec![&mut self.name];
+        fields.extend(
+            vec![
+                &mut self.w3c_id,
+                &mut self.org,
+                &mut self.org_link,
+                &mut self.email,
+                &mut self.link,
+                &mut self.note,
+            ]
+            .into_iter()
+            .filter_map(|field| field.as_mut()),
+        );
+        fields
+    }
+}
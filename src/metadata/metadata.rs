@@ -1,25 +1,52 @@
 use regex::Regex;
+use std::collections::HashMap;
 use titlecase::titlecase;
 
+use super::editor::Editor;
 use super::parse;
-use crate::config::SHORT_TO_LONG_STATUS;
-use crate::line::Line;
+use super::repository::Repository;
+use super::warning::Warning;
+use crate::config::{GROUP_TO_ORG, SHORT_TO_LONG_STATUS, SNAPSHOT_STATUSES};
+use crate::highlight;
+use crate::html;
+use crate::line::{Line, Location};
+use crate::shorthand::{self, MarkupShorthands};
 use crate::spec::Spec;
 use crate::util::date::Date;
+use crate::util::regex::PAIR_REG;
 
 #[derive(Debug, Clone, Default)]
 pub struct Metadata {
     pub has_keys: bool,
     pub abs: Vec<String>,
+    pub boilerplate: Vec<(String, bool)>,
     pub canonical_url: Option<String>,
-    pub date: Date,
+    pub custom_warning_text: Option<String>,
+    pub custom_warning_title: Option<String>,
+    pub date: Option<Date>,
+    pub deadline: Option<Date>,
+    pub default_highlight: Option<String>,
     pub ed: Option<String>,
-    pub editors: Vec<String>,
+    pub editors: Vec<Editor>,
+    pub feedback: Vec<String>,
+    pub former_editors: Vec<Editor>,
     pub group: Option<String>,
+    pub implementation_report: Option<String>,
+    pub issue_trackers: Vec<(String, String)>,
     pub level: Option<String>,
+    pub logo: Option<String>,
+    pub mailing_list: Option<String>,
+    pub markup_shorthands: Vec<(String, bool)>,
+    pub mailing_list_archives: Option<String>,
     pub shortname: Option<String>,
     pub raw_status: Option<String>,
+    pub repository: Option<Repository>,
+    pub test_suite: Option<String>,
+    pub text_macros: Vec<(String, String)>,
     pub title: Option<String>,
+    pub tr: Option<String>,
+    pub warning: Option<Warning>,
+    pub work_status: Option<String>,
 }
 
 impl Metadata {
@@ -27,7 +54,7 @@ impl Metadata {
         Self::default()
     }
 
-    pub fn add_data(&mut self, key: &str, val: &str, line_num: Option<u32>) {
+    pub fn add_data(&mut self, key: &str, val: &str, location: Option<Location>) {
         let mut key = key.trim().to_string();
 
         if key != "ED" && key != "TR" && key != "URL" {
@@ -39,35 +66,124 @@ impl Metadata {
                 let val = parse::parse_vec(val);
                 self.abs.extend(val);
             }
+            "Boilerplate" => {
+                let val = match parse::parse_boilerplate(val) {
+                    Some(val) => val,
+                    None => die!(
+                        "The \"Boilerplate\" field must be a list like \"omit conformance, index no\"."; location
+                    ),
+                };
+                self.boilerplate.extend(val);
+            }
             "Canonical Url" => {
                 let val = val.to_owned();
                 self.canonical_url = Some(val);
             }
+            "Custom Warning Text" => {
+                let val = val.trim().to_owned();
+                self.custom_warning_text = Some(val);
+            }
+            "Custom Warning Title" => {
+                let val = val.trim().to_owned();
+                self.custom_warning_title = Some(val);
+            }
             "Date" => {
                 let val = match parse::parse_date(val) {
                     Ok(val) => val,
                     Err(_) => {
-                        die!("The \"Date\" field must be in the format YYYY-MM-DD."; line_num)
+                        die!("The \"Date\" field must be in the format YYYY-MM-DD."; location)
                     }
                 };
-                self.date = val;
+                self.date = Some(val);
+            }
+            "Deadline" => {
+                let val = match parse::parse_date(val) {
+                    Ok(val) => val,
+                    Err(_) => {
+                        die!("The \"Deadline\" field must be in the format YYYY-MM-DD."; location)
+                    }
+                };
+                self.deadline = Some(val);
+            }
+            "Default Highlight" => {
+                let val = match highlight::find_language(val) {
+                    Some(lang) => lang.to_owned(),
+                    None => {
+                        die!("Unknown \"Default Highlight\" language \"{}\".", val.trim(); location)
+                    }
+                };
+                self.default_highlight = Some(val);
             }
             "ED" => {
                 let val = val.to_owned();
                 self.ed = Some(val);
             }
-            "Editor" => {
-                let val = parse::parse_editor(val);
-                self.editors.extend(val);
+            "Editor" | "Former Editor" => {
+                let val = match parse::parse_editor(val) {
+                    Some(val) => val,
+                    None => die!("{} lines must start with the name of the editor.", key; location),
+                };
+                if key == "Editor" {
+                    self.editors.push(val);
+                } else {
+                    self.former_editors.push(val);
+                }
+            }
+            "Feedback" => {
+                let val = val.trim().to_owned();
+                self.feedback.push(val);
             }
             "Group" => {
                 let val = val.to_owned();
                 self.group = Some(val);
             }
+            "Implementation Report" => {
+                let val = val.trim().to_owned();
+                self.implementation_report = Some(val);
+            }
+            "Issue Tracking" => {
+                let val = match parse::parse_issue_tracker(val) {
+                    Some(val) => val,
+                    None => {
+                        die!("Issue Tracking lines must contain a label followed by a URL."; location)
+                    }
+                };
+                self.issue_trackers.push(val);
+            }
             "Level" => {
                 let val = parse::parse_level(val);
                 self.level = Some(val);
             }
+            "Logo" => {
+                let val = val.trim().to_owned();
+                self.logo = Some(val);
+            }
+            "Markup Shorthands" => {
+                let val = match shorthand::parse_settings(val) {
+                    Ok(val) => val,
+                    Err(err) => die!(
+                        "The \"Markup Shorthands\" field must be a list like \"css no, markdown no\". {}", err; location
+                    ),
+                };
+                self.markup_shorthands.extend(val);
+            }
+            "Mailing List" => {
+                let val = val.trim().to_owned();
+                self.mailing_list = Some(val);
+            }
+            "Mailing List Archives" => {
+                let val = val.trim().to_owned();
+                self.mailing_list_archives = Some(val);
+            }
+            "Repository" => {
+                let val = match parse::parse_repository(val) {
+                    Some(val) => val,
+                    None => {
+                        die!("The \"Repository\" field must contain a URL or a GitHub \"user/repo\"."; location)
+                    }
+                };
+                self.repository = Some(val);
+            }
             "Shortname" => {
                 let val = val.to_owned();
                 self.shortname = Some(val);
@@ -76,11 +192,51 @@ impl Metadata {
                 let val = val.to_owned();
                 self.raw_status = Some(val);
             }
+            "Test Suite" => {
+                let val = val.trim().to_owned();
+                self.test_suite = Some(val);
+            }
+            "Text Macro" => {
+                let val = match parse::parse_text_macro(val) {
+                    Some(val) => val,
+                    None => die!(
+                        "Text Macro lines must contain a macro name followed by the macro text."; location
+                    ),
+                };
+                self.text_macros.push(val);
+            }
             "Title" => {
                 let val = val.to_owned();
                 self.title = Some(val);
             }
-            _ => die!("Unknown metadata key \"{}\".", key; line_num),
+            "TR" => {
+                let val = val.to_owned();
+                self.tr = Some(val);
+            }
+            "Warning" => {
+                let val = match parse::parse_warning(val) {
+                    Some(val) => val,
+                    None => die!(
+                        "Unknown \"Warning\" value \"{}\". It must be Obsolete, Not Ready, New Version <url>, Replaced By <url>, Commit <sha> <url>, Branch <name> <url> or Custom.",
+                        val.trim();
+                        location
+                    ),
+                };
+                self.warning = Some(val);
+            }
+            "Work Status" => {
+                let val = match parse::parse_work_status(val) {
+                    Some(val) => val,
+                    None => die!(
+                        "Unknown \"Work Status\" value \"{}\". It must be one of: {}.",
+                        val.trim(),
+                        parse::WORK_STATUSES.join(", ");
+                        location
+                    ),
+                };
+                self.work_status = Some(val);
+            }
+            _ => die!("Unknown metadata key \"{}\".", key; location),
         }
 
         self.has_keys = true;
@@ -95,26 +251,74 @@ impl Metadata {
 
         // Abstract
         self.abs.extend(other.abs.into_iter());
+        // Boilerplate
+        self.boilerplate.extend(other.boilerplate);
         // Canonical Url
         if other.canonical_url.is_some() {
             self.canonical_url = other.canonical_url;
         }
+        // Custom Warning Text
+        if other.custom_warning_text.is_some() {
+            self.custom_warning_text = other.custom_warning_text;
+        }
+        // Custom Warning Title
+        if other.custom_warning_title.is_some() {
+            self.custom_warning_title = other.custom_warning_title;
+        }
         // Date
-        self.date = other.date;
+        if other.date.is_some() {
+            self.date = other.date;
+        }
+        // Deadline
+        if other.deadline.is_some() {
+            self.deadline = other.deadline;
+        }
+        // Default Highlight
+        if other.default_highlight.is_some() {
+            self.default_highlight = other.default_highlight;
+        }
         // ED
         if other.ed.is_some() {
             self.ed = other.ed;
         }
         // Editor
         self.editors.extend(other.editors.into_iter());
+        // Former Editor
+        self.former_editors.extend(other.former_editors);
+        // Feedback
+        self.feedback.extend(other.feedback);
         // Group
         if other.group.is_some() {
             self.group = other.group;
         }
+        // Implementation Report
+        if other.implementation_report.is_some() {
+            self.implementation_report = other.implementation_report;
+        }
+        // Issue Tracking
+        self.issue_trackers.extend(other.issue_trackers);
         // Level
         if other.level.is_some() {
             self.level = other.level;
         }
+        // Logo
+        if other.logo.is_some() {
+            self.logo = other.logo;
+        }
+        // Mailing List
+        if other.mailing_list.is_some() {
+            self.mailing_list = other.mailing_list;
+        }
+        // Mailing List Archives
+        if other.mailing_list_archives.is_some() {
+            self.mailing_list_archives = other.mailing_list_archives;
+        }
+        // Markup Shorthands
+        self.markup_shorthands.extend(other.markup_shorthands);
+        // Repository
+        if other.repository.is_some() {
+            self.repository = other.repository;
+        }
         // Shortname
         if other.shortname.is_some() {
             self.shortname = other.shortname;
@@ -123,47 +327,279 @@ impl Metadata {
         if other.raw_status.is_some() {
             self.raw_status = other.raw_status;
         }
+        // Test Suite
+        if other.test_suite.is_some() {
+            self.test_suite = other.test_suite;
+        }
+        // Text Macro
+        self.text_macros.extend(other.text_macros);
         // Title
         if other.title.is_some() {
             self.title = other.title;
         }
+        // TR
+        if other.tr.is_some() {
+            self.tr = other.tr;
+        }
+        // Warning
+        if other.warning.is_some() {
+            self.warning = other.warning;
+        }
+        // Work Status
+        if other.work_status.is_some() {
+            self.work_status = other.work_status;
+        }
     }
 
     pub fn fill_macros(&self, doc: &mut Spec) {
         let macros = &mut doc.macros;
 
-        macros.insert(
-            "date",
-            self.date
-                .format(&format!("{} %B %Y", self.date.day()))
-                .to_string(),
-        );
-        macros.insert("isodate", self.date.to_string());
+        if let Some(ref date) = self.date {
+            macros.insert(
+                "date".to_owned(),
+                date.format(&format!("{} %B %Y", date.day())).to_string(),
+            );
+            macros.insert("isodate".to_owned(), date.to_string());
+            macros.insert("cdate".to_owned(), date.format("%Y%m%d").to_string());
+            macros.insert("year".to_owned(), date.year().to_string());
+        }
+
+        if let Some(ref deadline) = self.deadline {
+            macros.insert(
+                "deadline".to_owned(),
+                deadline
+                    .format(&format!("{} %B %Y", deadline.day()))
+                    .to_string(),
+            );
+            macros.insert("isodeadline".to_owned(), deadline.to_string());
+        }
+        if let Some(ref work_status) = self.work_status {
+            macros.insert("workstatus".to_owned(), work_status.clone());
+        }
 
         if let Some(ref level) = self.level {
-            macros.insert("level", level.clone());
+            macros.insert("level".to_owned(), level.clone());
         }
         if let Some(ref shortname) = self.shortname {
-            macros.insert("shortname", shortname.clone());
+            macros.insert("shortname".to_owned(), shortname.clone());
+            macros.insert("vshortname".to_owned(), self.vshortname().unwrap());
         }
         if let Some(ref raw_status) = self.raw_status {
             macros.insert(
-                "longstatus",
+                "longstatus".to_owned(),
                 SHORT_TO_LONG_STATUS
                     .get(raw_status.as_str())
                     .unwrap()
                     .to_string(),
             );
+            let status = self.status().unwrap();
+            if raw_status.starts_with("w3c/") {
+                macros.insert(
+                    "w3c-stylesheet-url".to_owned(),
+                    format!("https://www.w3.org/StyleSheets/TR/2016/W3C-{}", status),
+                );
+                macros.insert("w3c-status".to_owned(), status.clone());
+            }
+            macros.insert("status".to_owned(), status);
         }
         if let Some(ref title) = self.title {
-            macros.insert("title", title.clone());
-            macros.insert("spectitle", title.clone());
+            macros.insert("title".to_owned(), title.clone());
+            macros.insert("spectitle".to_owned(), title.clone());
+        }
+        if let Some(ref repository) = self.repository {
+            macros.insert("repository".to_owned(), repository.name.clone());
+        }
+        if let Some(ref tr) = self.tr {
+            macros.insert("latest".to_owned(), tr.clone());
+        }
+        if let Some(version) = self.version() {
+            macros.insert("version".to_owned(), version.clone());
+            macros.insert(
+                "url".to_owned(),
+                self.canonical_url.clone().unwrap_or(version),
+            );
+        } else if let Some(ref canonical_url) = self.canonical_url {
+            macros.insert("url".to_owned(), canonical_url.clone());
+        }
+        if !self.abs.is_empty() {
+            macros.insert("abstract".to_owned(), self.abs.join("\n"));
+            macros.insert(
+                "abstractattr".to_owned(),
+                html::helper::escape_html(
+                    &self.abs.join(" ").replace("<<", "<").replace(">>", ">"),
+                ),
+            );
+        }
+
+        match self.warning {
+            Some(Warning::NewVersion(ref url)) => {
+                macros.insert("newversion".to_owned(), url.clone());
+            }
+            Some(Warning::ReplacedBy(ref url)) => {
+                macros.insert("replacedby".to_owned(), url.clone());
+            }
+            Some(Warning::Commit { ref sha, ref url }) => {
+                macros.insert("snapshotid".to_owned(), sha.clone());
+                macros.insert("snapshoturl".to_owned(), url.clone());
+            }
+            Some(Warning::Branch { ref name, ref url }) => {
+                macros.insert("snapshotid".to_owned(), name.clone());
+                macros.insert("snapshoturl".to_owned(), url.clone());
+            }
+            _ => {}
+        }
+        if let Some(ref title) = self.custom_warning_title {
+            macros.insert("customwarningtitle".to_owned(), title.clone());
+        } else if let Some(Warning::Custom) = self.warning {
+            macros.insert("customwarningtitle".to_owned(), "Warning".to_owned());
+        }
+        if let Some(ref text) = self.custom_warning_text {
+            macros.insert("customwarningtext".to_owned(), text.clone());
+        }
+
+        // user-defined macros
+        for (name, val) in self.text_macros.iter() {
+            macros.insert(name.clone(), val.clone());
+        }
+    }
+
+    // Expand the text macros used in metadata values (e.g. "ED: https://example.org/[SHORTNAME]/").
+    // Escaped macros stay escaped while the values are used as macros themselves.
+    pub fn replace_macros(&mut self, macros: &HashMap<String, String>) {
+        for val in self.macro_vals_mut() {
+            *val = html::helper::replace_macros_keeping_escapes(val, macros);
+        }
+    }
+
+    // Turn the escaped macros of metadata values into literal text, once the values have
+    // been filled into the macros.
+    pub fn unescape_macros(&mut self) {
+        for val in self.macro_vals_mut() {
+            *val = html::helper::unescape_macros(val);
+        }
+    }
+
+    // The metadata values that can use text macros.
+    fn macro_vals_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        let editor_vals = self
+            .editors
+            .iter_mut()
+            .chain(self.former_editors.iter_mut())
+            .flat_map(|editor| editor.fields_mut());
+        let issue_tracker_vals = self
+            .issue_trackers
+            .iter_mut()
+            .flat_map(|(label, url)| vec![label, url]);
+        let repository_vals = self
+            .repository
+            .iter_mut()
+            .flat_map(|repository| vec![&mut repository.url, &mut repository.name]);
+        self.abs
+            .iter_mut()
+            .chain(editor_vals)
+            .chain(self.feedback.iter_mut())
+            .chain(issue_tracker_vals)
+            .chain(repository_vals)
+            .chain(
+                vec![
+                    &mut self.canonical_url,
+                    &mut self.ed,
+                    &mut self.group,
+                    &mut self.level,
+                    &mut self.mailing_list,
+                    &mut self.mailing_list_archives,
+                    &mut self.shortname,
+                    &mut self.title,
+                    &mut self.tr,
+                ]
+                .into_iter()
+                .filter_map(|val| val.as_mut()),
+            )
+    }
+
+    // Whether the boilerplate section (e.g. "index") or style (e.g. "style-counters") is
+    // generated. Everything is, unless "Boilerplate" turns it off.
+    pub fn is_boilerplate_on(&self, name: &str) -> bool {
+        self.boilerplate
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .is_none_or(|(_, on)| *on)
+    }
+
+    // The markup shorthands of the spec: the defaults, as changed by "Markup Shorthands".
+    pub fn shorthands(&self) -> MarkupShorthands {
+        let mut shorthands = MarkupShorthands::default();
+        for (name, on) in self.markup_shorthands.iter() {
+            shorthands.set(name, *on);
+        }
+        shorthands
+    }
+
+    // The org that publishes the spec (e.g. "w3c"), given by the prefix of the status or
+    // else by the group.
+    pub fn org(&self) -> Option<String> {
+        if let Some(pos) = self.raw_status.as_ref().and_then(|status| status.find('/')) {
+            return Some(self.raw_status.as_ref().unwrap()[..pos].to_lowercase());
+        }
+        self.group
+            .as_ref()
+            .and_then(|group| GROUP_TO_ORG.get(group.to_lowercase().as_str()))
+            .map(|org| org.to_string())
+    }
+
+    // The status without its org prefix (e.g. "WD" for "w3c/WD"). Steps on the way to
+    // a status are reported as the status itself.
+    pub fn status(&self) -> Option<String> {
+        self.raw_status.as_ref().map(|raw_status| {
+            let status = match raw_status.find('/') {
+                Some(pos) => &raw_status[pos + 1..],
+                None => raw_status.as_str(),
+            };
+            match status {
+                "FPWD" | "LCWD" => "WD",
+                "WG-NOTE" | "IG-NOTE" => "NOTE",
+                _ => status,
+            }
+            .to_owned()
+        })
+    }
+
+    // The shortname with its level (e.g. "css-foo-3").
+    pub fn vshortname(&self) -> Option<String> {
+        self.shortname.as_ref().map(|shortname| match self.level {
+            Some(ref level) if !level.is_empty() => format!("{}-{}", shortname, level),
+            _ => shortname.clone(),
+        })
+    }
+
+    // The URL of this version of the spec: the TR URL for published snapshots, and the
+    // ED URL otherwise.
+    pub fn version(&self) -> Option<String> {
+        let is_snapshot = self
+            .raw_status
+            .as_ref()
+            .is_some_and(|raw_status| SNAPSHOT_STATUSES.contains(raw_status.as_str()));
+        if is_snapshot {
+            self.tr.clone().or_else(|| self.ed.clone())
+        } else {
+            self.ed.clone()
         }
     }
 
     pub fn compute_implicit_metadata(&mut self) {
+        if self.date.is_none() {
+            self.date = Some(Date::now());
+        }
         if self.canonical_url.as_ref().map_or(true, |url| url == "ED") {
             self.canonical_url = self.ed.clone();
+        } else if self.canonical_url.as_ref().is_some_and(|url| url == "TR") {
+            self.canonical_url = self.tr.clone();
+        }
+        if self.issue_trackers.is_empty() {
+            if let Some(issues_url) = self.repository.as_ref().and_then(|r| r.issues_url()) {
+                self.issue_trackers.push(("GitHub".to_owned(), issues_url));
+            }
         }
     }
 
@@ -171,6 +607,28 @@ impl Metadata {
         if !self.has_keys {
             die!("No metadata provided.");
         }
+        if let Some(ref raw_status) = self.raw_status {
+            if (raw_status == "w3c/CR" || raw_status == "w3c/PR") && self.deadline.is_none() {
+                die!(
+                    "The \"Deadline\" field is required for the {} status.",
+                    raw_status
+                );
+            }
+        }
+        if let (Some(deadline), Some(date)) = (&self.deadline, &self.date) {
+            if deadline.date <= date.date {
+                die!(
+                    "The \"Deadline\" ({}) must be later than the \"Date\" ({}).",
+                    deadline,
+                    date
+                );
+            }
+        }
+        if let Some(Warning::Custom) = self.warning {
+            if self.custom_warning_text.is_none() {
+                die!("The \"Warning: Custom\" metadata requires \"Custom Warning Text\".");
+            }
+        }
     }
 }
 
@@ -184,8 +642,6 @@ pub fn parse_metadata(lines: &[Line]) -> (Metadata, Vec<Line>) {
         static ref PRE_END_TAG: Regex = Regex::new(r"</pre>\s*").unwrap();
         // </xmp> end tag
         static ref XMP_END_TAG: Regex = Regex::new(r"</xmp>\s*").unwrap();
-        // pair reg
-        static ref PAIR_REG: Regex = Regex::new(r"([^:]+):\s*(.*)").unwrap();
     }
 
     let mut md = Metadata::new();
@@ -210,24 +666,24 @@ pub fn parse_metadata(lines: &[Line]) -> (Metadata, Vec<Line>) {
         } else if in_metadata {
             if last_key.is_some() && line.text.trim().is_empty() {
                 // if the line is empty, continue the previous key
-                md.add_data(last_key.unwrap(), &line.text, Some(line.index));
+                md.add_data(last_key.unwrap(), &line.text, Some(line.location()));
             } else if PAIR_REG.is_match(&line.text) {
                 // handle key-val pair
                 let caps = PAIR_REG.captures(&line.text).unwrap();
                 let key = caps.get(1).map_or("", |k| k.as_str());
                 let val = caps.get(2).map_or("", |v| v.as_str());
-                md.add_data(key, val, Some(line.index));
+                md.add_data(key, val, Some(line.location()));
                 last_key = Some(key);
             } else {
                 // wrong key-val pair
-                die!("Incorrectly formatted metadata"; Some(line.index));
+                die!("Incorrectly formatted metadata"; Some(line.location()));
             }
         } else if TITLE_REG.is_match(&line.text) {
             // handle title
             if md.title.is_none() {
                 let caps = TITLE_REG.captures(&line.text).unwrap();
                 let title = caps.get(1).map_or("", |m| m.as_str());
-                md.add_data("Title", title, Some(line.index));
+                md.add_data("Title", title, Some(line.location()));
             }
             new_lines.push(line.clone());
         } else {
@@ -238,3 +694,105 @@ pub fn parse_metadata(lines: &[Line]) -> (Metadata, Vec<Line>) {
 
     (md, new_lines)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn new_metadata(group: Option<&str>, raw_status: Option<&str>) -> Metadata {
+        Metadata {
+            group: group.map(|group| group.to_string()),
+            raw_status: raw_status.map(|raw_status| raw_status.to_string()),
+            ..Metadata::new()
+        }
+    }
+
+    #[test]
+    fn test_org() {
+        assert_eq!(
+            new_metadata(None, Some("w3c/ED")).org(),
+            Some("w3c".to_string())
+        );
+        assert_eq!(
+            new_metadata(Some("csswg"), Some("ED")).org(),
+            Some("w3c".to_string())
+        );
+        assert_eq!(
+            new_metadata(Some("WHATWG"), None).org(),
+            Some("whatwg".to_string())
+        );
+        assert_eq!(new_metadata(Some("unknown"), Some("ED")).org(), None);
+        assert_eq!(new_metadata(None, None).org(), None);
+    }
+
+    #[test]
+    fn test_fill_macros() {
+        let md = Metadata {
+            abs: vec!["A <<foo>> spec.".to_owned(), "Second line.".to_owned()],
+            date: Some(Date::new(NaiveDate::from_ymd_opt(2020, 2, 3).unwrap())),
+            ed: Some("https://example.org/foo/".to_owned()),
+            level: Some("3".to_owned()),
+            raw_status: Some("w3c/CR".to_owned()),
+            shortname: Some("foo".to_owned()),
+            title: Some("Foo Level 3".to_owned()),
+            tr: Some("https://www.w3.org/TR/foo-3/".to_owned()),
+            ..Metadata::new()
+        };
+        let mut doc = Spec::default();
+        md.fill_macros(&mut doc);
+
+        let expected = [
+            ("date", "3 February 2020"),
+            ("isodate", "2020-02-03"),
+            ("cdate", "20200203"),
+            ("year", "2020"),
+            ("level", "3"),
+            ("shortname", "foo"),
+            ("vshortname", "foo-3"),
+            ("longstatus", "W3C Candidate Recommendation"),
+            ("status", "CR"),
+            ("w3c-status", "CR"),
+            (
+                "w3c-stylesheet-url",
+                "https://www.w3.org/StyleSheets/TR/2016/W3C-CR",
+            ),
+            ("title", "Foo Level 3"),
+            ("spectitle", "Foo Level 3"),
+            ("latest", "https://www.w3.org/TR/foo-3/"),
+            ("version", "https://www.w3.org/TR/foo-3/"),
+            ("url", "https://www.w3.org/TR/foo-3/"),
+            ("abstract", "A <<foo>> spec.\nSecond line."),
+            ("abstractattr", "A &lt;foo&gt; spec. Second line."),
+        ];
+        for (name, val) in expected.iter() {
+            assert_eq!(
+                doc.macros.get(*name).map(|v| v.as_str()),
+                Some(*val),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_fill_macros_of_editors_drafts() {
+        let md = Metadata {
+            canonical_url: Some("https://example.org/canonical/".to_owned()),
+            ed: Some("https://example.org/foo/".to_owned()),
+            raw_status: Some("LS".to_owned()),
+            shortname: Some("foo".to_owned()),
+            tr: Some("https://www.w3.org/TR/foo/".to_owned()),
+            ..Metadata::new()
+        };
+        let mut doc = Spec::default();
+        md.fill_macros(&mut doc);
+
+        assert_eq!(doc.macros["vshortname"], "foo");
+        assert_eq!(doc.macros["status"], "LS");
+        assert_eq!(doc.macros["version"], "https://example.org/foo/");
+        assert_eq!(doc.macros["url"], "https://example.org/canonical/");
+        assert!(!doc.macros.contains_key("w3c-status"));
+        assert!(!doc.macros.contains_key("date"));
+    }
+}
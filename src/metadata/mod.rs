@@ -1,2 +1,5 @@
+pub mod editor;
 pub mod metadata;
 mod parse;
+pub mod repository;
+pub mod warning;
@@ -1,5 +1,39 @@
+use regex::Regex;
+
+use super::editor::Editor;
+use super::repository::Repository;
+use super::warning::Warning;
 use crate::util::date::{Date, ParseResult};
 
+// Parse the sections of a "Boilerplate" list like "omit conformance, copyright no,
+// index yes".
+pub fn parse_boilerplate(val: &str) -> Option<Vec<(String, bool)>> {
+    val.split(',')
+        .map(|setting| setting.trim())
+        .filter(|setting| !setting.is_empty())
+        .map(|setting| {
+            let pieces: Vec<&str> = setting.split_whitespace().collect();
+            match pieces.as_slice() {
+                ["omit", name] => Some((name.to_lowercase(), false)),
+                [name, "yes"] => Some((name.to_lowercase(), true)),
+                [name, "no"] => Some((name.to_lowercase(), false)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+pub const WORK_STATUSES: [&str; 8] = [
+    "completed",
+    "stable",
+    "testing",
+    "refining",
+    "revising",
+    "exploring",
+    "rewriting",
+    "abandoned",
+];
+
 pub fn parse_date(val: &str) -> ParseResult {
     if val == "now" {
         Ok(Date::now())
@@ -11,9 +45,78 @@ pub fn parse_date(val: &str) -> ParseResult {
     }
 }
 
-pub fn parse_editor(val: &str) -> Vec<String> {
-    // TODO: parse editor
-    vec![String::from("TODO: parse editor") + " " + val]
+// Parse the comma-separated pieces of an editor (commas can be escaped as "\,"). After the
+// name, the pieces are recognized by their form: "w3cid 1234" is a W3C ID, an address
+// with "@" is an email, a URL right after the organization links to the organization
+// and any other URL to the editor, and the first plain piece is the organization while a
+// later one is a note (e.g. "Former").
+pub fn parse_editor(val: &str) -> Option<Editor> {
+    lazy_static! {
+        static ref W3C_ID_REG: Regex = Regex::new(r"^w3cid\s+(\d+)$").unwrap();
+        static ref EMAIL_REG: Regex = Regex::new(r"^(mailto:)?([^\s@]+@[^\s@]+)$").unwrap();
+        static ref URL_REG: Regex = Regex::new(r"^\w+://\S+$").unwrap();
+    }
+
+    let mut pieces = split_escaped_commas(val).into_iter();
+    let name = pieces.next().filter(|name| !name.is_empty())?;
+    let mut editor = Editor {
+        name,
+        ..Default::default()
+    };
+    let mut after_org = false;
+
+    for piece in pieces.filter(|piece| !piece.is_empty()) {
+        if let Some(caps) = W3C_ID_REG.captures(&piece) {
+            editor.w3c_id = Some(caps[1].to_owned());
+        } else if let Some(caps) = EMAIL_REG.captures(&piece) {
+            editor.email = Some(caps[2].to_owned());
+        } else if URL_REG.is_match(&piece) {
+            if after_org && editor.org_link.is_none() {
+                editor.org_link = Some(piece);
+            } else {
+                editor.link = Some(piece);
+            }
+        } else if editor.org.is_none() {
+            editor.org = Some(piece);
+            after_org = true;
+            continue;
+        } else {
+            editor.note = Some(piece);
+        }
+        after_org = false;
+    }
+
+    Some(editor)
+}
+
+fn split_escaped_commas(val: &str) -> Vec<String> {
+    let mut pieces: Vec<String> = vec![String::new()];
+    let mut chars = val.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&',') => {
+                pieces.last_mut().unwrap().push(',');
+                chars.next();
+            }
+            ',' => pieces.push(String::new()),
+            _ => pieces.last_mut().unwrap().push(c),
+        }
+    }
+    pieces
+        .into_iter()
+        .map(|piece| piece.trim().to_owned())
+        .collect()
+}
+
+// Parse an issue tracker like "GitHub https://github.com/foo/bar/issues" into its label and
+// its URL. A lone URL is its own label.
+pub fn parse_issue_tracker(val: &str) -> Option<(String, String)> {
+    let val = val.trim();
+    match val.rfind(char::is_whitespace) {
+        Some(pos) => Some((val[..pos].trim().to_owned(), val[pos + 1..].to_owned())),
+        None if !val.is_empty() => Some((val.to_owned(), val.to_owned())),
+        None => None,
+    }
 }
 
 pub fn parse_level(val: &str) -> String {
@@ -24,6 +127,245 @@ pub fn parse_level(val: &str) -> String {
     }
 }
 
+// Parse a repository like "w3c/csswg-drafts", "https://github.com/w3c/csswg-drafts" or
+// "https://example.org/repo Example Repo".
+pub fn parse_repository(val: &str) -> Option<Repository> {
+    lazy_static! {
+        static ref GITHUB_URL_REG: Regex =
+            Regex::new(r"^https?://github\.com/([\w.-]+/[\w.-]+?)/?$").unwrap();
+        static ref GITHUB_SHORTHAND_REG: Regex = Regex::new(r"^[\w.-]+/[\w.-]+$").unwrap();
+    }
+
+    let mut pieces = val.split_whitespace();
+    let url = pieces.next()?;
+    let name = pieces.collect::<Vec<&str>>().join(" ");
+
+    let github_name = match GITHUB_URL_REG.captures(url) {
+        Some(caps) => Some(caps[1].to_owned()),
+        None if GITHUB_SHORTHAND_REG.is_match(url) => Some(url.to_owned()),
+        None => None,
+    };
+    let repository = match github_name {
+        Some(github_name) => Repository {
+            url: format!("https://github.com/{}", github_name),
+            name: if name.is_empty() { github_name } else { name },
+            is_github: true,
+        },
+        None => Repository {
+            url: url.to_owned(),
+            name: if name.is_empty() {
+                url.to_owned()
+            } else {
+                name
+            },
+            is_github: false,
+        },
+    };
+    Some(repository)
+}
+
+// Parse a text macro like "FOO some text" into its lowercased name and its text.
+pub fn parse_text_macro(val: &str) -> Option<(String, String)> {
+    lazy_static! {
+        static ref TEXT_MACRO_REG: Regex =
+            Regex::new(r"^([A-Z\d-]*[A-Z][A-Z\d-]*)\s+(.*)$").unwrap();
+    }
+    TEXT_MACRO_REG
+        .captures(val.trim())
+        .map(|caps| (caps[1].to_lowercase(), caps[2].to_owned()))
+}
+
 pub fn parse_vec(val: &str) -> Vec<String> {
     vec![val.to_owned()]
 }
+
+// Parse a warning like "Obsolete", "Replaced By https://example.org/" or
+// "Commit 1234abc https://example.org/commit/1234abc".
+pub fn parse_warning(val: &str) -> Option<Warning> {
+    lazy_static! {
+        static ref WARNING_REG: Regex =
+            Regex::new(r"(?i)^(obsolete|not ready|new version|replaced by|commit|branch|custom)(?:\s+(\S+))?(?:\s+(\S+))?$")
+                .unwrap();
+    }
+
+    let caps = WARNING_REG.captures(val.trim())?;
+    let first = caps.get(2).map(|m| m.as_str().to_owned());
+    let second = caps.get(3).map(|m| m.as_str().to_owned());
+    let warning = match (caps[1].to_lowercase().as_str(), first, second) {
+        ("obsolete", None, None) => Warning::Obsolete,
+        ("not ready", None, None) => Warning::NotReady,
+        ("new version", Some(url), None) => Warning::NewVersion(url),
+        ("replaced by", Some(url), None) => Warning::ReplacedBy(url),
+        ("commit", Some(sha), Some(url)) => Warning::Commit { sha, url },
+        ("branch", Some(name), Some(url)) => Warning::Branch { name, url },
+        ("custom", None, None) => Warning::Custom,
+        _ => return None,
+    };
+    Some(warning)
+}
+
+pub fn parse_work_status(val: &str) -> Option<String> {
+    let val = val.trim().to_lowercase();
+    if WORK_STATUSES.contains(&val.as_str()) {
+        Some(val)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_macro() {
+        assert_eq!(
+            parse_text_macro(" FOO-2 some  text "),
+            Some(("foo-2".to_owned(), "some  text".to_owned()))
+        );
+        assert_eq!(parse_text_macro("FOO"), None);
+        assert_eq!(parse_text_macro("foo text"), None);
+        assert_eq!(parse_text_macro("0 text"), None);
+    }
+
+    #[test]
+    fn test_parse_editor() {
+        let editor = parse_editor(
+            "Tab Atkins Jr., w3cid 42199, Google, http://www.google.com/, http://xanthir.com/contact/",
+        )
+        .unwrap();
+        assert_eq!(editor.name, "Tab Atkins Jr.");
+        assert_eq!(editor.w3c_id.as_deref(), Some("42199"));
+        assert_eq!(editor.org.as_deref(), Some("Google"));
+        assert_eq!(editor.org_link.as_deref(), Some("http://www.google.com/"));
+        assert_eq!(editor.link.as_deref(), Some("http://xanthir.com/contact/"));
+        assert_eq!(editor.email, None);
+        assert_eq!(editor.note, None);
+    }
+
+    #[test]
+    fn test_parse_editor_with_email_and_note() {
+        let editor = parse_editor("A, mailto:a@example.org, Foo\\, Inc., Former").unwrap();
+        assert_eq!(editor.email.as_deref(), Some("a@example.org"));
+        assert_eq!(editor.org.as_deref(), Some("Foo, Inc."));
+        assert_eq!(editor.note.as_deref(), Some("Former"));
+        assert_eq!(editor.link, None);
+    }
+
+    #[test]
+    fn test_parse_editor_link_without_org() {
+        let editor = parse_editor("A, https://a.example/").unwrap();
+        assert_eq!(editor.link.as_deref(), Some("https://a.example/"));
+        assert_eq!(editor.org_link, None);
+        assert!(parse_editor(", Google").is_none());
+    }
+
+    #[test]
+    fn test_parse_issue_tracker() {
+        assert_eq!(
+            parse_issue_tracker("Inline In Spec #issues-index"),
+            Some(("Inline In Spec".to_owned(), "#issues-index".to_owned()))
+        );
+        assert_eq!(
+            parse_issue_tracker("https://example.org/issues"),
+            Some((
+                "https://example.org/issues".to_owned(),
+                "https://example.org/issues".to_owned()
+            ))
+        );
+        assert_eq!(parse_issue_tracker("  "), None);
+    }
+
+    #[test]
+    fn test_parse_repository() {
+        let repository = parse_repository("w3c/csswg-drafts").unwrap();
+        assert_eq!(repository.url, "https://github.com/w3c/csswg-drafts");
+        assert_eq!(repository.name, "w3c/csswg-drafts");
+        assert_eq!(
+            repository.issues_url().as_deref(),
+            Some("https://github.com/w3c/csswg-drafts/issues")
+        );
+
+        let repository = parse_repository("https://github.com/w3c/csswg-drafts/ CSS").unwrap();
+        assert_eq!(repository.url, "https://github.com/w3c/csswg-drafts");
+        assert_eq!(repository.name, "CSS");
+
+        let repository = parse_repository("https://example.org/repo Example Repo").unwrap();
+        assert_eq!(repository.url, "https://example.org/repo");
+        assert_eq!(repository.name, "Example Repo");
+        assert_eq!(repository.issues_url(), None);
+
+        assert!(parse_repository("").is_none());
+    }
+
+    #[test]
+    fn test_parse_warning() {
+        assert!(matches!(parse_warning("Obsolete"), Some(Warning::Obsolete)));
+        assert!(matches!(
+            parse_warning(" not ready "),
+            Some(Warning::NotReady)
+        ));
+        assert!(matches!(parse_warning("Custom"), Some(Warning::Custom)));
+        assert!(matches!(
+            parse_warning("New Version https://example.com/v2/"),
+            Some(Warning::NewVersion(ref url)) if url == "https://example.com/v2/"
+        ));
+        assert!(matches!(
+            parse_warning("Replaced By https://example.com/new/"),
+            Some(Warning::ReplacedBy(ref url)) if url == "https://example.com/new/"
+        ));
+        assert!(matches!(
+            parse_warning("Commit 1a2b3c https://example.com/commit/1a2b3c"),
+            Some(Warning::Commit { ref sha, ref url })
+                if sha == "1a2b3c" && url == "https://example.com/commit/1a2b3c"
+        ));
+        assert!(matches!(
+            parse_warning("Branch dev https://example.com/dev/"),
+            Some(Warning::Branch { ref name, ref url })
+                if name == "dev" && url == "https://example.com/dev/"
+        ));
+    }
+
+    #[test]
+    fn test_parse_invalid_warning() {
+        assert!(parse_warning("").is_none());
+        assert!(parse_warning("Obsolete now").is_none());
+        assert!(parse_warning("New Version").is_none());
+        assert!(parse_warning("Commit 1a2b3c").is_none());
+        assert!(parse_warning("Branch dev https://example.com/dev/ extra").is_none());
+        assert!(parse_warning("Deprecated").is_none());
+    }
+
+    #[test]
+    fn test_parse_work_status() {
+        assert_eq!(parse_work_status(" Refining "), Some("refining".to_owned()));
+        assert_eq!(parse_work_status("abandoned"), Some("abandoned".to_owned()));
+        assert_eq!(parse_work_status("finished"), None);
+        assert_eq!(parse_work_status(""), None);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("2020-02-29").unwrap().to_string(), "2020-02-29");
+        assert_eq!(parse_date("now").unwrap().date, Date::now().date);
+        assert!(parse_date("2019-02-29").is_err());
+        assert!(parse_date("29-02-2020").is_err());
+        assert!(parse_date("").is_err());
+    }
+
+    #[test]
+    fn test_parse_boilerplate() {
+        assert_eq!(
+            parse_boilerplate("omit conformance, Copyright no, index yes,"),
+            Some(vec![
+                ("conformance".to_owned(), false),
+                ("copyright".to_owned(), false),
+                ("index".to_owned(), true),
+            ])
+        );
+        assert_eq!(parse_boilerplate(""), Some(Vec::new()));
+        assert_eq!(parse_boilerplate("conformance"), None);
+        assert_eq!(parse_boilerplate("index yes, omit"), None);
+        assert_eq!(parse_boilerplate("index maybe"), None);
+    }
+}
@@ -0,0 +1,19 @@
+// The source repository of the spec, given as a GitHub "user/repo" shorthand, a GitHub
+// URL, or any URL followed by the name to show for it.
+#[derive(Debug, Clone, Default)]
+pub struct Repository {
+    pub url: String,
+    pub name: String,
+    pub is_github: bool,
+}
+
+impl Repository {
+    // Only GitHub repositories have a known issue tracker.
+    pub fn issues_url(&self) -> Option<String> {
+        if self.is_github {
+            Some(format!("{}/issues", self.url))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,26 @@
+// A warning banner shown at the top of the spec, given by the "Warning" metadata.
+#[derive(Debug, Clone)]
+pub enum Warning {
+    Obsolete,
+    NotReady,
+    NewVersion(String),
+    ReplacedBy(String),
+    Commit { sha: String, url: String },
+    Branch { name: String, url: String },
+    Custom,
+}
+
+impl Warning {
+    // The name of the boilerplate file of the banner (e.g. "warning-obsolete").
+    pub fn boilerplate_name(&self) -> &'static str {
+        match self {
+            Warning::Obsolete => "warning-obsolete",
+            Warning::NotReady => "warning-not-ready",
+            Warning::NewVersion(_) => "warning-new-version",
+            Warning::ReplacedBy(_) => "warning-replaced-by",
+            Warning::Commit { .. } => "warning-commit",
+            Warning::Branch { .. } => "warning-branch",
+            Warning::Custom => "warning-custom",
+        }
+    }
+}
@@ -0,0 +1,255 @@
+mod render;
+
+use regex::Regex;
+
+use crate::line::Line;
+use crate::spec::Spec;
+
+pub use render::render_diagram;
+
+// A piece of a railroad diagram. "Opt:" and "Star:" are written with the other pieces
+// (e.g. "Opt: foo" is a choice between skipping and "foo").
+#[derive(Debug)]
+pub enum Node {
+    Terminal(String),
+    NonTerminal(String),
+    Comment(String),
+    Skip,
+    Sequence(Vec<Node>),
+    // the items, and the index of the one on the main line
+    Choice(usize, Vec<Node>),
+    // the repeated item, and what comes between the repetitions
+    OneOrMore(Box<Node>, Box<Node>),
+}
+
+// A "Command: text" line of the diagram, with its indentation.
+struct DslLine<'a> {
+    indent: usize,
+    // the command as written (e.g. "Opt"), and lowercased for matching
+    name: String,
+    command: String,
+    text: String,
+    line: &'a Line,
+}
+
+// Parse the lines of a "<pre class=railroad>" block (e.g. "Seq:", then the indented
+// "T: foo" and "N: bar") into a diagram. Several top-level lines are a sequence.
+pub fn parse_diagram(lines: &[Line]) -> Node {
+    lazy_static! {
+        static ref LINE_REG: Regex = Regex::new(r"^\s*([A-Za-z]+)\s*:\s*(.*?)\s*$").unwrap();
+    }
+
+    let dsl_lines: Vec<DslLine> = lines
+        .iter()
+        .map(|line| {
+            let caps = match LINE_REG.captures(&line.text) {
+                Some(caps) => caps,
+                None => die!(
+                    "Incorrectly formatted railroad line: \"{}\". Lines must look like \"T: foo\".",
+                    line.text.trim();
+                    Some(line.location())
+                ),
+            };
+            DslLine {
+                indent: indent_of(&line.text),
+                name: caps[1].to_owned(),
+                command: caps[1].to_lowercase(),
+                text: caps[2].to_owned(),
+                line,
+            }
+        })
+        .collect();
+
+    if dsl_lines.is_empty() {
+        return Node::Sequence(Vec::new());
+    }
+    let mut pos = 0;
+    let mut nodes = parse_nodes(&dsl_lines, &mut pos, None);
+    if nodes.len() == 1 {
+        nodes.pop().unwrap()
+    } else {
+        Node::Sequence(nodes)
+    }
+}
+
+// Add the railroad stylesheet if the spec has a diagram.
+pub fn add_railroad_style(doc: &mut Spec) {
+    let document = doc.document.as_ref().unwrap();
+    if document.select_first("svg.railroad-diagram").is_ok() {
+        doc.extra_styles
+            .insert("railroad", include_str!("../style/railroad.css"));
+    }
+}
+
+// Parse the sibling lines from the current position, along with their children, until a
+// line is indented no more than their parent.
+fn parse_nodes(lines: &[DslLine], pos: &mut usize, parent_indent: Option<usize>) -> Vec<Node> {
+    let level = lines[*pos].indent;
+    let mut nodes: Vec<Node> = Vec::new();
+
+    while *pos < lines.len() {
+        let line = &lines[*pos];
+        if line.indent != level {
+            if parent_indent.is_some_and(|indent| line.indent <= indent) {
+                break;
+            }
+            die!(
+                "The railroad line \"{}\" is indented inconsistently with its siblings.",
+                line.line.text.trim();
+                Some(line.line.location())
+            );
+        }
+
+        *pos += 1;
+        let children = if *pos < lines.len() && lines[*pos].indent > level {
+            parse_nodes(lines, pos, Some(level))
+        } else {
+            Vec::new()
+        };
+        nodes.push(new_node(line, children));
+    }
+
+    nodes
+}
+
+fn new_node(line: &DslLine, mut children: Vec<Node>) -> Node {
+    let location = Some(line.line.location());
+    let is_leaf = match line.command.as_str() {
+        "t" | "terminal" | "n" | "nonterminal" | "c" | "comment" | "s" | "skip" => true,
+        "seq" | "sequence" | "and" | "or" | "choice" | "opt" | "optional" | "plus"
+        | "oneormore" | "star" | "zeroormore" => false,
+        _ => die!("Unknown railroad command \"{}:\".", line.name; location),
+    };
+    if is_leaf && !children.is_empty() {
+        die!("The railroad \"{}:\" command can't have children.", line.name; location);
+    }
+    if is_leaf && line.text.is_empty() && !matches!(line.command.as_str(), "s" | "skip") {
+        die!("The railroad \"{}:\" command needs some text.", line.name; location);
+    }
+    if !is_leaf && children.is_empty() {
+        die!("The railroad \"{}:\" command needs indented children.", line.name; location);
+    }
+
+    match line.command.as_str() {
+        "t" | "terminal" => Node::Terminal(line.text.clone()),
+        "n" | "nonterminal" => Node::NonTerminal(line.text.clone()),
+        "c" | "comment" => Node::Comment(line.text.clone()),
+        "s" | "skip" => Node::Skip,
+        "seq" | "sequence" | "and" => Node::Sequence(children),
+        "or" | "choice" => {
+            let normal = if line.text.is_empty() {
+                0
+            } else {
+                match line.text.parse::<usize>() {
+                    Ok(normal) if normal < children.len() => normal,
+                    _ => die!(
+                        "The \"{}:\" argument must be the index of one of its {} children.",
+                        line.name,
+                        children.len();
+                        location
+                    ),
+                }
+            };
+            Node::Choice(normal, children)
+        }
+        "opt" | "optional" => {
+            if children.len() != 1 {
+                die!("The railroad \"{}:\" command takes one child.", line.name; location);
+            }
+            Node::Choice(1, vec![Node::Skip, children.pop().unwrap()])
+        }
+        "plus" | "oneormore" | "star" | "zeroormore" => {
+            if children.len() > 2 {
+                die!(
+                    "The railroad \"{}:\" command takes an item and an optional separator.",
+                    line.name;
+                    location
+                );
+            }
+            let repeat = if children.len() == 2 {
+                children.pop().unwrap()
+            } else {
+                Node::Skip
+            };
+            let item = children.pop().unwrap();
+            let one_or_more = Node::OneOrMore(Box::new(item), Box::new(repeat));
+            if line.command == "star" || line.command == "zeroormore" {
+                Node::Choice(1, vec![Node::Skip, one_or_more])
+            } else {
+                one_or_more
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn indent_of(text: &str) -> usize {
+    text.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> String {
+        let lines: Vec<Line> = text
+            .lines()
+            .enumerate()
+            .map(|(index, text)| Line {
+                index: index as u32 + 1,
+                text: text.to_owned(),
+                file: None,
+            })
+            .collect();
+        format!("{:?}", parse_diagram(&lines))
+    }
+
+    #[test]
+    fn test_parse_diagram() {
+        assert_eq!(parse(""), "Sequence([])");
+        assert_eq!(parse("T: foo"), "Terminal(\"foo\")");
+        assert_eq!(
+            parse("N: a\nc: some comment\nSkip:"),
+            "Sequence([NonTerminal(\"a\"), Comment(\"some comment\"), Skip])"
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_diagram() {
+        assert_eq!(
+            parse(
+                "Seq:\n\
+                 \x20   Or: 1\n\
+                 \x20       T: a\n\
+                 \x20       T: b\n\
+                 \x20   Opt:\n\
+                 \x20       N: c\n\
+                 \tStar:\n\
+                 \t\tN: d\n\
+                 \t\tT: ,\n\
+                 \x20   Plus:\n\
+                 \x20       N: e"
+            ),
+            "Sequence([\
+             Choice(1, [Terminal(\"a\"), Terminal(\"b\")]), \
+             Choice(1, [Skip, NonTerminal(\"c\")]), \
+             Choice(1, [Skip, OneOrMore(NonTerminal(\"d\"), Terminal(\",\"))]), \
+             OneOrMore(NonTerminal(\"e\"), Skip)])"
+        );
+    }
+
+    #[test]
+    fn test_render_diagram() {
+        let svg = render_diagram(&Node::Sequence(vec![
+            Node::Terminal("a<b".to_owned()),
+            Node::Comment("note".to_owned()),
+        ]));
+        assert!(svg.starts_with("<svg class=\"railroad-diagram\""));
+        assert!(svg.ends_with("</g></svg>"));
+        assert!(svg.contains(">a&lt;b</text>"));
+        assert!(svg.contains("<text class=\"comment\""));
+    }
+}
@@ -0,0 +1,263 @@
+use super::Node;
+use crate::html;
+
+// the radius of the arcs
+const AR: f64 = 10.0;
+// the minimum vertical space between the items of a choice or a loop
+const VS: f64 = 8.0;
+const CHAR_WIDTH: f64 = 8.5;
+const COMMENT_CHAR_WIDTH: f64 = 7.0;
+const PADDING: f64 = 20.0;
+
+// The size of a node: its width, and how far it extends above and below its line.
+struct Size {
+    width: f64,
+    up: f64,
+    down: f64,
+}
+
+// Render the diagram as an inline SVG, between a start and an end marker.
+pub fn render_diagram(node: &Node) -> String {
+    let size = node.size();
+    let x = PADDING;
+    let y = PADDING + size.up.max(AR);
+    let width = PADDING * 2.0 + size.width + 60.0;
+    let height = y + size.down.max(AR) + PADDING;
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<svg class=\"railroad-diagram\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\"><g transform=\"translate(.5 .5)\">",
+        width, height, width, height
+    ));
+    out.push_str(&format!(
+        "<path d=\"M {} {} v 20 m 10 -20 v 20 m -10 -10 h 30\"></path>",
+        x,
+        y - 10.0
+    ));
+    node.draw(&mut out, x + 30.0, y, size.width);
+    out.push_str(&format!(
+        "<path d=\"M {} {} h 30 m -10 -10 v 20 m 10 -20 v 20\"></path>",
+        x + 30.0 + size.width,
+        y
+    ));
+    out.push_str("</g></svg>");
+    out
+}
+
+impl Node {
+    fn size(&self) -> Size {
+        match self {
+            Node::Terminal(text) | Node::NonTerminal(text) => Size {
+                width: text.chars().count() as f64 * CHAR_WIDTH + 20.0,
+                up: 11.0,
+                down: 11.0,
+            },
+            Node::Comment(text) => Size {
+                width: text.chars().count() as f64 * COMMENT_CHAR_WIDTH + 10.0,
+                up: 8.0,
+                down: 8.0,
+            },
+            Node::Skip => Size {
+                width: 0.0,
+                up: 0.0,
+                down: 0.0,
+            },
+            Node::Sequence(items) => {
+                let mut size = Size {
+                    width: 0.0,
+                    up: 0.0,
+                    down: 0.0,
+                };
+                for item in items.iter() {
+                    let item_size = item.size();
+                    size.width += item_size.width + if item.needs_space() { 20.0 } else { 0.0 };
+                    size.up = size.up.max(item_size.up);
+                    size.down = size.down.max(item_size.down);
+                }
+                if items.first().is_some_and(Node::needs_space) {
+                    size.width -= 10.0;
+                }
+                if items.last().is_some_and(Node::needs_space) {
+                    size.width -= 10.0;
+                }
+                size
+            }
+            Node::Choice(normal, items) => {
+                let offsets = choice_offsets(*normal, items);
+                let mut size = Size {
+                    width: 0.0,
+                    up: 0.0,
+                    down: 0.0,
+                };
+                for (item, offset) in items.iter().zip(offsets.iter()) {
+                    let item_size = item.size();
+                    size.width = size.width.max(item_size.width);
+                    size.up = size.up.max(item_size.up - offset);
+                    size.down = size.down.max(item_size.down + offset);
+                }
+                size.width += AR * 4.0;
+                size
+            }
+            Node::OneOrMore(item, repeat) => {
+                let item_size = item.size();
+                let repeat_size = repeat.size();
+                let distance = loop_distance(item, repeat);
+                Size {
+                    width: item_size.width.max(repeat_size.width) + AR * 2.0,
+                    up: item_size.up,
+                    down: distance + repeat_size.down,
+                }
+            }
+        }
+    }
+
+    // Whether the node is set apart from its neighbours in a sequence.
+    fn needs_space(&self) -> bool {
+        !matches!(self, Node::Skip | Node::Choice(..))
+    }
+
+    // Draw the node with its line at (x, y), stretched to the given width.
+    fn draw(&self, out: &mut String, x: f64, y: f64, width: f64) {
+        let size = self.size();
+        let gaps = (width - size.width) / 2.0;
+        draw_line(out, x, y, gaps);
+        draw_line(out, x + gaps + size.width, y, gaps);
+        let x = x + gaps;
+
+        match self {
+            Node::Terminal(text) | Node::NonTerminal(text) => {
+                let (class, radius) = match self {
+                    Node::Terminal(_) => ("terminal", 10),
+                    _ => ("non-terminal", 0),
+                };
+                out.push_str(&format!(
+                    "<g class=\"{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"22\" rx=\"{}\" ry=\"{}\"></rect><text x=\"{}\" y=\"{}\">{}</text></g>",
+                    class,
+                    x,
+                    y - 11.0,
+                    size.width,
+                    radius,
+                    radius,
+                    x + size.width / 2.0,
+                    y + 4.0,
+                    html::helper::escape_html(text)
+                ));
+            }
+            Node::Comment(text) => {
+                out.push_str(&format!(
+                    "<g class=\"comment\"><text class=\"comment\" x=\"{}\" y=\"{}\">{}</text></g>",
+                    x + size.width / 2.0,
+                    y + 5.0,
+                    html::helper::escape_html(text)
+                ));
+            }
+            Node::Skip => {}
+            Node::Sequence(items) => {
+                let mut x = x;
+                for (i, item) in items.iter().enumerate() {
+                    let item_width = item.size().width;
+                    if item.needs_space() && i > 0 {
+                        draw_line(out, x, y, 10.0);
+                        x += 10.0;
+                    }
+                    item.draw(out, x, y, item_width);
+                    x += item_width;
+                    if item.needs_space() && i + 1 < items.len() {
+                        draw_line(out, x, y, 10.0);
+                        x += 10.0;
+                    }
+                }
+            }
+            Node::Choice(normal, items) => {
+                let inner_width = size.width - AR * 4.0;
+                let right = x + AR * 2.0 + inner_width;
+                for (item, offset) in items.iter().zip(choice_offsets(*normal, items)) {
+                    if offset == 0.0 {
+                        draw_line(out, x, y, AR * 2.0);
+                        draw_line(out, right, y, AR * 2.0);
+                    } else {
+                        // the sweep of the arcs that turn toward the item, and back
+                        let (dir, toward, back) = if offset > 0.0 {
+                            (1.0, 1, 0)
+                        } else {
+                            (-1.0, 0, 1)
+                        };
+                        let vertical = offset - AR * 2.0 * dir;
+                        out.push_str(&format!(
+                            "<path d=\"M {} {} a {ar} {ar} 0 0 {} {ar} {} v {} a {ar} {ar} 0 0 {} {ar} {}\"></path>",
+                            x,
+                            y,
+                            toward,
+                            AR * dir,
+                            vertical,
+                            back,
+                            AR * dir,
+                            ar = AR
+                        ));
+                        out.push_str(&format!(
+                            "<path d=\"M {} {} a {ar} {ar} 0 0 {} {ar} {} v {} a {ar} {ar} 0 0 {} {ar} {}\"></path>",
+                            right,
+                            y + offset,
+                            back,
+                            -AR * dir,
+                            -vertical,
+                            toward,
+                            -AR * dir,
+                            ar = AR
+                        ));
+                    }
+                    item.draw(out, x + AR * 2.0, y + offset, inner_width);
+                }
+            }
+            Node::OneOrMore(item, repeat) => {
+                let inner_width = size.width - AR * 2.0;
+                let distance = loop_distance(item, repeat);
+                draw_line(out, x, y, AR);
+                item.draw(out, x + AR, y, inner_width);
+                draw_line(out, x + AR + inner_width, y, AR);
+                // the way back, from the end of the item to its start
+                out.push_str(&format!(
+                    "<path d=\"M {} {} a {ar} {ar} 0 0 1 {ar} {ar} v {} a {ar} {ar} 0 0 1 -{ar} {ar}\"></path>",
+                    x + AR + inner_width,
+                    y,
+                    distance - AR * 2.0,
+                    ar = AR
+                ));
+                repeat.draw(out, x + AR, y + distance, inner_width);
+                out.push_str(&format!(
+                    "<path d=\"M {} {} a {ar} {ar} 0 0 1 -{ar} -{ar} v {} a {ar} {ar} 0 0 1 {ar} -{ar}\"></path>",
+                    x + AR,
+                    y + distance,
+                    -(distance - AR * 2.0),
+                    ar = AR
+                ));
+            }
+        }
+    }
+}
+
+// The vertical offsets of the items of a choice from its line, which the normal item is on.
+fn choice_offsets(normal: usize, items: &[Node]) -> Vec<f64> {
+    let sizes: Vec<Size> = items.iter().map(Node::size).collect();
+    let distance = |i: usize| (AR * 2.0).max(sizes[i].down + VS + sizes[i + 1].up);
+
+    let mut offsets = vec![0.0; items.len()];
+    for i in (0..normal).rev() {
+        offsets[i] = offsets[i + 1] - distance(i);
+    }
+    for i in normal + 1..items.len() {
+        offsets[i] = offsets[i - 1] + distance(i - 1);
+    }
+    offsets
+}
+
+// The vertical distance between the item of a loop and the way back.
+fn loop_distance(item: &Node, repeat: &Node) -> f64 {
+    (AR * 2.0).max(item.size().down + VS + repeat.size().up)
+}
+
+fn draw_line(out: &mut String, x: f64, y: f64, length: f64) {
+    if length > 0.0 {
+        out.push_str(&format!("<path d=\"M {} {} h {}\"></path>", x, y, length));
+    }
+}
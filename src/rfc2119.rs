@@ -0,0 +1,206 @@
+use kuchiki::NodeRef;
+use regex::Regex;
+
+use crate::html;
+use crate::spec::Spec;
+
+lazy_static! {
+    // the RFC 2119 keywords, as written in the running text of specs
+    pub static ref KEYWORD_REG: Regex =
+        Regex::new(r"\b(may|must|should|shall|optional|recommended|required)\b").unwrap();
+}
+
+// Elements whose text is never checked for keywords.
+const SKIPPED_ELEMENTS: [&str; 8] = ["pre", "xmp", "code", "script", "style", "svg", "a", "dfn"];
+
+// Classes of the elements whose contents are non-normative.
+const NON_NORMATIVE_CLASSES: [&str; 3] = ["note", "example", "non-normative"];
+
+// Classes of the elements whose contents are never checked for keywords: issues, keywords
+// that are already marked, and text where keywords are allowed on purpose.
+const SKIPPED_CLASSES: [&str; 3] = ["issue", "rfc2119", "allow-2119"];
+
+// Mark the RFC 2119 keywords of normative text with "<span class=rfc2119>", and warn about
+// keywords in non-normative text, where they are likely to be accidental.
+pub fn mark_rfc2119_keywords(doc: &mut Spec) {
+    visit_text(doc, |text_node, normative| {
+        let text = text_node.as_text().unwrap().borrow().clone();
+        if !KEYWORD_REG.is_match(&text) {
+            return;
+        }
+
+        if !normative {
+            for keyword in KEYWORD_REG.find_iter(&text) {
+                warn!(
+                    "Accidental RFC 2119 keyword \"{}\" in non-normative text (use \"might\", \"can\" or \"has to\", or mark it with class=allow-2119): \"{}\".",
+                    keyword.as_str(),
+                    text.trim()
+                );
+            }
+            return;
+        }
+
+        let mut last_end = 0;
+        for keyword in KEYWORD_REG.find_iter(&text) {
+            text_node.insert_before(NodeRef::new_text(&text[last_end..keyword.start()]));
+            text_node.insert_before(html::node::new_text_element(
+                "span",
+                btreemap! {
+                    "class" => "rfc2119".to_owned(),
+                },
+                keyword.as_str(),
+            ));
+            last_end = keyword.end();
+        }
+        text_node.insert_before(NodeRef::new_text(&text[last_end..]));
+        text_node.detach();
+    });
+}
+
+// Visit the text nodes of the main content in document order, telling whether each of
+// them is normative. Text is non-normative inside notes, examples and elements marked
+// "non-normative", and in the sections whose heading is marked "non-normative".
+pub fn visit_text(doc: &Spec, mut visit: impl FnMut(&NodeRef, bool)) {
+    let document = doc.document.as_ref().unwrap();
+    let main = match document.select_first("main") {
+        Ok(main) => main.as_node().clone(),
+        Err(_) => return,
+    };
+
+    // (level, non-normative) of the headings of the current sections
+    let mut sections: Vec<(u32, bool)> = Vec::new();
+    visit_children(&main, false, &mut sections, &mut visit);
+}
+
+fn visit_children(
+    node: &NodeRef,
+    non_normative: bool,
+    sections: &mut Vec<(u32, bool)>,
+    visit: &mut impl FnMut(&NodeRef, bool),
+) {
+    // the visitor can replace the text nodes, so the children are collected first
+    let children: Vec<NodeRef> = node.children().collect();
+
+    for child in children.iter() {
+        if child.as_text().is_some() {
+            let in_non_normative_section = sections.iter().any(|(_, non_normative)| *non_normative);
+            visit(child, !(non_normative || in_non_normative_section));
+            continue;
+        }
+
+        let name = match child.as_element() {
+            Some(data) => data.name.local.to_string(),
+            None => continue,
+        };
+
+        if let Some(level) = heading_level(&name) {
+            sections.retain(|(l, _)| *l < level);
+            sections.push((level, html::node::has_class(child, "non-normative")));
+        }
+
+        if SKIPPED_ELEMENTS.contains(&name.as_str())
+            || SKIPPED_CLASSES
+                .iter()
+                .any(|class| html::node::has_class(child, class))
+            || html::node::get_attr(child, "data-fill-with").is_some()
+        {
+            continue;
+        }
+
+        let non_normative = non_normative
+            || NON_NORMATIVE_CLASSES
+                .iter()
+                .any(|class| html::node::has_class(child, class));
+        visit_children(child, non_normative, sections, visit);
+    }
+}
+
+fn heading_level(name: &str) -> Option<u32> {
+    match name {
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::*;
+
+    fn visit_html(html: &str) -> Vec<(String, bool)> {
+        let document = kuchiki::parse_html().one(html);
+        let main = document.select_first("main").unwrap();
+        let mut texts = Vec::new();
+        visit_children(
+            main.as_node(),
+            false,
+            &mut Vec::new(),
+            &mut |node, normative| {
+                let text = node.as_text().unwrap().borrow().trim().to_owned();
+                if !text.is_empty() {
+                    texts.push((text, normative));
+                }
+            },
+        );
+        texts
+    }
+
+    #[test]
+    fn test_keyword_reg() {
+        let keywords: Vec<&str> = KEYWORD_REG
+            .find_iter("UAs must, and should, be required; mayhem is not optional")
+            .map(|keyword| keyword.as_str())
+            .collect();
+        assert_eq!(keywords, vec!["must", "should", "required", "optional"]);
+    }
+
+    #[test]
+    fn test_visit_text() {
+        let texts = visit_html(
+            "<main>\
+             <p>one</p>\
+             <div class=note>two</div>\
+             <p>three <code>four</code> <a>five</a></p>\
+             <p class=allow-2119>six</p>\
+             <div data-fill-with=index>seven</div>\
+             </main>",
+        );
+        assert_eq!(
+            texts,
+            vec![
+                ("one".to_owned(), true),
+                ("two".to_owned(), false),
+                ("three".to_owned(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visit_text_of_non_normative_sections() {
+        let texts = visit_html(
+            "<main>\
+             <h2 class=non-normative>Intro</h2>\
+             <p>one</p>\
+             <h3>Details</h3>\
+             <p>two</p>\
+             <h2>Conformance</h2>\
+             <p>three</p>\
+             </main>",
+        );
+        assert_eq!(
+            texts,
+            vec![
+                ("Intro".to_owned(), false),
+                ("one".to_owned(), false),
+                ("Details".to_owned(), false),
+                ("two".to_owned(), false),
+                ("Conformance".to_owned(), true),
+                ("three".to_owned(), true),
+            ]
+        );
+    }
+}
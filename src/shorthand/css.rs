@@ -0,0 +1,195 @@
+use regex::{Captures, Regex};
+
+use crate::html;
+use crate::util;
+
+// Replace the CSS autolink shorthands in a piece of text:
+//
+// - "<<foo>>" links to the type <foo> ("<<'foo'>>" and "<<foo()>>" link to the property
+//   and the function instead)
+// - "'foo'" links to the property foo ("'@bar/foo'" links to the descriptor foo of @bar)
+// - "''foo''" links to the value foo ("''bar/foo''" links to the value foo of bar), and
+//   other CSS code is just marked up
+pub fn replace_css_shorthands(text: &str) -> String {
+    lazy_static! {
+        // type reg
+        static ref TYPE_REG: Regex = Regex::new(r"<<([^<>]+)>>").unwrap();
+        // value reg
+        static ref VALUE_REG: Regex = Regex::new(r"''([^']+)''").unwrap();
+        // property reg
+        static ref PROPERTY_REG: Regex =
+            Regex::new(r"'(-*[a-zA-Z@][\w-]*(?:/-*[a-zA-Z][\w-]*)?)'").unwrap();
+    }
+
+    let text = util::regex::replace_all(&TYPE_REG, text, replace_type);
+    let text = util::regex::replace_all(&VALUE_REG, &text, replace_value);
+    replace_property(&PROPERTY_REG, &text)
+}
+
+fn replace_type(caps: &Captures) -> String {
+    link_type(&caps[1])
+}
+
+// Link a type (e.g. "length" for "<<length>>"), or a property or function written as a
+// type (e.g. "'foo'" and "foo()").
+pub fn link_type(inner: &str) -> String {
+    let inner = inner.trim();
+    let escaped = format!("&lt;{}>", html::helper::escape_html(inner));
+
+    if inner.len() > 2 && inner.starts_with('\'') && inner.ends_with('\'') {
+        let name = &inner[1..inner.len() - 1];
+        format!(
+            "<a class=\"production\" data-link-type=\"property\" data-lt=\"{}\">{}</a>",
+            name, escaped
+        )
+    } else if inner.ends_with("()") {
+        format!(
+            "<a class=\"production\" data-link-type=\"function\" data-lt=\"{}\">{}</a>",
+            inner, escaped
+        )
+    } else {
+        // a range like "<<length [0,∞]>>" is displayed, but not part of the link text
+        let name = inner.split_whitespace().next().unwrap_or(inner);
+        if name == inner {
+            format!(
+                "<a class=\"production\" data-link-type=\"type\">{}</a>",
+                escaped
+            )
+        } else {
+            format!(
+                "<a class=\"production\" data-link-type=\"type\" data-lt=\"&lt;{}>\">{}</a>",
+                name, escaped
+            )
+        }
+    }
+}
+
+fn replace_value(caps: &Captures) -> String {
+    lazy_static! {
+        static ref LINKABLE_REG: Regex =
+            Regex::new(r"^(?:([\w@-]+(?:\(\))?)/)?(-*[a-zA-Z@][\w-]*(?:\(\))?)$").unwrap();
+    }
+
+    let inner = &caps[1];
+    match LINKABLE_REG.captures(inner) {
+        Some(link_caps) => {
+            let value = &link_caps[2];
+            let link_type = if value.ends_with("()") {
+                "function"
+            } else {
+                "value"
+            };
+            match link_caps.get(1) {
+                Some(link_for) => format!(
+                    "<a class=\"css\" data-link-type=\"{}\" data-link-for=\"{}\">{}</a>",
+                    link_type,
+                    link_for.as_str(),
+                    value
+                ),
+                None => format!(
+                    "<a class=\"css\" data-link-type=\"{}\">{}</a>",
+                    link_type, value
+                ),
+            }
+        }
+        None => format!("<span class=\"css\">{}</span>", inner),
+    }
+}
+
+// The quotes of "'foo'" must not be part of a word (e.g. "don't"), so the boundaries are
+// checked by hand.
+fn replace_property(reg: &Regex, text: &str) -> String {
+    let is_boundary = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric() && c != '\'');
+
+    let mut new = String::with_capacity(text.len());
+    let mut last_match = 0;
+    for caps in reg.captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        if !is_boundary(text[..m.start()].chars().last())
+            || !is_boundary(text[m.end()..].chars().next())
+        {
+            continue;
+        }
+
+        new.push_str(&text[last_match..m.start()]);
+        new.push_str(&link_property(&caps[1]));
+        last_match = m.end();
+    }
+    new.push_str(&text[last_match..]);
+    new
+}
+
+// Link a property (e.g. "foo"), or a descriptor of an at-rule (e.g. "@bar/foo").
+pub fn link_property(name: &str) -> String {
+    match name.find('/') {
+        Some(pos) => format!(
+            "<a class=\"property\" data-link-type=\"descriptor\" data-link-for=\"{}\">{}</a>",
+            &name[..pos],
+            &name[pos + 1..]
+        ),
+        None => format!(
+            "<a class=\"property\" data-link-type=\"property\">{}</a>",
+            name
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_shorthands() {
+        assert_eq!(
+            replace_css_shorthands("<<length>>"),
+            "<a class=\"production\" data-link-type=\"type\">&lt;length></a>"
+        );
+        assert_eq!(
+            replace_css_shorthands("<<length [0,∞]>>"),
+            "<a class=\"production\" data-link-type=\"type\" data-lt=\"&lt;length>\">&lt;length [0,∞]></a>"
+        );
+        assert_eq!(
+            replace_css_shorthands("<<'width'>>"),
+            "<a class=\"production\" data-link-type=\"property\" data-lt=\"width\">&lt;&#39;width&#39;></a>"
+        );
+        assert_eq!(
+            replace_css_shorthands("<<calc()>>"),
+            "<a class=\"production\" data-link-type=\"function\" data-lt=\"calc()\">&lt;calc()></a>"
+        );
+    }
+
+    #[test]
+    fn test_value_shorthands() {
+        assert_eq!(
+            replace_css_shorthands("''auto''"),
+            "<a class=\"css\" data-link-type=\"value\">auto</a>"
+        );
+        assert_eq!(
+            replace_css_shorthands("''width/auto''"),
+            "<a class=\"css\" data-link-type=\"value\" data-link-for=\"width\">auto</a>"
+        );
+        assert_eq!(
+            replace_css_shorthands("''calc()''"),
+            "<a class=\"css\" data-link-type=\"function\">calc()</a>"
+        );
+        assert_eq!(
+            replace_css_shorthands("''width: auto''"),
+            "<span class=\"css\">width: auto</span>"
+        );
+    }
+
+    #[test]
+    fn test_property_shorthands() {
+        assert_eq!(
+            replace_css_shorthands("'width' and '@page/size'"),
+            "<a class=\"property\" data-link-type=\"property\">width</a> and \
+             <a class=\"property\" data-link-type=\"descriptor\" data-link-for=\"@page\">size</a>"
+        );
+    }
+
+    #[test]
+    fn test_property_shorthands_need_word_boundaries() {
+        assert_eq!(replace_css_shorthands("don't and isn't"), "don't and isn't");
+        assert_eq!(replace_css_shorthands("'1px'"), "'1px'");
+    }
+}
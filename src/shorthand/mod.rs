@@ -0,0 +1,270 @@
+pub mod css;
+
+use regex::Regex;
+use std::collections::BTreeMap;
+
+use crate::html;
+
+// Elements whose contents are never touched by shorthands.
+const OPAQUE_ELEMENTS: [&str; 6] = ["pre", "xmp", "code", "script", "style", "svg"];
+
+// The markup shorthands that can be turned on or off, and whether they are on by default.
+pub const SHORTHAND_DEFAULTS: [(&str, bool); 1] = [("css", true)];
+
+// The other shorthands of Bikeshed, which have no parser here yet. They can only be turned
+// off.
+const UNSUPPORTED_SHORTHANDS: [&str; 7] = [
+    "algorithm",
+    "biblio",
+    "dfn",
+    "idl",
+    "markdown",
+    "markup",
+    "repository-links",
+];
+
+// The markup shorthands in effect at some point of the document.
+#[derive(Debug, Clone)]
+pub struct MarkupShorthands {
+    flags: BTreeMap<&'static str, bool>,
+}
+
+impl Default for MarkupShorthands {
+    fn default() -> Self {
+        MarkupShorthands {
+            flags: SHORTHAND_DEFAULTS.iter().cloned().collect(),
+        }
+    }
+}
+
+impl MarkupShorthands {
+    pub fn is_on(&self, name: &str) -> bool {
+        self.flags.get(name).cloned().unwrap_or(false)
+    }
+
+    pub fn set(&mut self, name: &str, on: bool) {
+        if let Some(flag) = self.flags.get_mut(name) {
+            *flag = on;
+        }
+    }
+}
+
+// Parse a list of shorthand settings like "css no, markdown no". Turning on a shorthand
+// that has no parser yet is an error, rather than a setting that silently does nothing.
+pub fn parse_settings(val: &str) -> Result<Vec<(String, bool)>, String> {
+    let mut settings: Vec<(String, bool)> = Vec::new();
+
+    for setting in val.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let pieces: Vec<String> = setting
+            .split_whitespace()
+            .map(|piece| piece.to_lowercase())
+            .collect();
+        let (name, on) = match pieces.as_slice() {
+            [name, on] if ["yes", "on", "true"].contains(&on.as_str()) => (name, true),
+            [name, on] if ["no", "off", "false"].contains(&on.as_str()) => (name, false),
+            _ => {
+                return Err(format!(
+                    "\"{}\" must be a shorthand and \"yes\" or \"no\".",
+                    setting
+                ))
+            }
+        };
+        if UNSUPPORTED_SHORTHANDS.contains(&name.as_str()) {
+            if on {
+                return Err(format!("The \"{}\" shorthand isn't supported yet.", name));
+            }
+            continue;
+        }
+        if !SHORTHAND_DEFAULTS.iter().any(|(n, _)| *n == name) {
+            return Err(format!("Unknown shorthand \"{}\".", name));
+        }
+        settings.push((name.clone(), on));
+    }
+
+    Ok(settings)
+}
+
+pub fn process_shorthands(html: &str, shorthands: &MarkupShorthands) -> String {
+    replace_in_text(html, shorthands, |text, shorthands| {
+        if shorthands.is_on("css") {
+            css::replace_css_shorthands(text)
+        } else {
+            text.to_owned()
+        }
+    })
+}
+
+// An element whose "markup-shorthands" attribute changes the shorthands of its contents.
+struct Override {
+    tag_name: String,
+    // the nesting of elements with the same tag name, to find the end tag
+    nesting: u32,
+    shorthands: MarkupShorthands,
+    // a "<div>" with no other attributes only carried the shorthands, so its tags are
+    // removed
+    unwrap: bool,
+}
+
+// Apply the replacer to the text of the HTML, leaving tags, comments and the contents of
+// opaque elements alone. The replacer is given the shorthands in effect for the text, as
+// changed by the "markup-shorthands" attributes of its ancestors (e.g.
+// "<div markup-shorthands='css no'>"). Those attributes are removed from the output.
+pub fn replace_in_text(
+    html: &str,
+    shorthands: &MarkupShorthands,
+    replacer: impl Fn(&str, &MarkupShorthands) -> String,
+) -> String {
+    lazy_static! {
+        // "<<foo>>" is text rather than a tag, so it is matched first and skipped
+        static ref TAG_REG: Regex =
+            Regex::new(r"(?s)<<[^<>]*>>|<!--.*?-->|<(/?)([a-zA-Z][\w-]*)[^>]*>").unwrap();
+        static ref SHORTHANDS_ATTR_REG: Regex =
+            Regex::new(r#"(?i)\smarkup-shorthands(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'>]+))?"#)
+                .unwrap();
+    }
+
+    let mut new = String::with_capacity(html.len());
+    let mut text_start = 0;
+    let mut opaque_depth = 0;
+    let mut overrides: Vec<Override> = Vec::new();
+
+    for caps in TAG_REG.captures_iter(html) {
+        let m = caps.get(0).unwrap();
+        if m.as_str().starts_with("<<") {
+            continue;
+        }
+
+        let text = &html[text_start..m.start()];
+        let current = overrides.last().map_or(shorthands, |o| &o.shorthands);
+        if opaque_depth == 0 {
+            new.push_str(&replacer(text, current));
+        } else {
+            new.push_str(text);
+        }
+        text_start = m.end();
+
+        let tag_name = caps
+            .get(2)
+            .map_or(String::new(), |m| m.as_str().to_lowercase());
+        if tag_name.is_empty() {
+            new.push_str(m.as_str());
+            continue;
+        }
+        let is_end_tag = !caps[1].is_empty();
+        if OPAQUE_ELEMENTS.contains(&tag_name.as_str()) {
+            if !is_end_tag {
+                opaque_depth += 1;
+            } else if opaque_depth > 0 {
+                opaque_depth -= 1;
+            }
+        }
+
+        let mut tag = m.as_str().to_owned();
+        let attrs = if is_end_tag {
+            BTreeMap::new()
+        } else {
+            html::helper::parse_attributes(m.as_str())
+        };
+        if let Some(val) = attrs.get("markup-shorthands") {
+            let settings = match parse_settings(val) {
+                Ok(settings) => settings,
+                Err(err) => die!("Invalid markup-shorthands attribute \"{}\": {}", val, err),
+            };
+            let mut changed = overrides
+                .last()
+                .map_or(shorthands, |o| &o.shorthands)
+                .clone();
+            for (name, on) in settings.iter() {
+                changed.set(name, *on);
+            }
+            let unwrap = tag_name == "div" && attrs.len() == 1;
+            tag = if unwrap {
+                String::new()
+            } else {
+                SHORTHANDS_ATTR_REG.replace_all(&tag, "").into_owned()
+            };
+            overrides.push(Override {
+                tag_name,
+                nesting: 1,
+                shorthands: changed,
+                unwrap,
+            });
+        } else if let Some(o) = overrides.last_mut() {
+            if o.tag_name == tag_name {
+                if !is_end_tag {
+                    o.nesting += 1;
+                } else {
+                    o.nesting -= 1;
+                    if o.nesting == 0 && overrides.pop().unwrap().unwrap {
+                        tag = String::new();
+                    }
+                }
+            }
+        }
+        new.push_str(&tag);
+    }
+
+    let text = &html[text_start..];
+    let current = overrides.last().map_or(shorthands, |o| &o.shorthands);
+    if opaque_depth == 0 {
+        new.push_str(&replacer(text, current));
+    } else {
+        new.push_str(text);
+    }
+    new
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Mark the text that the css shorthands would be applied to.
+    fn mark_css_text(html: &str) -> String {
+        replace_in_text(html, &MarkupShorthands::default(), |text, shorthands| {
+            if shorthands.is_on("css") && !text.is_empty() {
+                format!("[{}]", text)
+            } else {
+                text.to_owned()
+            }
+        })
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        assert_eq!(
+            parse_settings("css no, markdown off"),
+            Ok(vec![("css".to_owned(), false)])
+        );
+        assert_eq!(
+            parse_settings(" CSS Yes "),
+            Ok(vec![("css".to_owned(), true)])
+        );
+        assert!(parse_settings("css").is_err());
+        assert!(parse_settings("css maybe").is_err());
+        assert!(parse_settings("css yes please").is_err());
+        assert!(parse_settings("foo yes").is_err());
+        assert!(parse_settings("markdown yes").is_err());
+    }
+
+    #[test]
+    fn test_replace_in_text() {
+        assert_eq!(
+            mark_css_text("<p>a<!-- b --></p><pre>c</pre><<d>>"),
+            "<p>[a]<!-- b --></p><pre>c</pre>[<<d>>]"
+        );
+    }
+
+    #[test]
+    fn test_replace_in_text_with_overrides() {
+        assert_eq!(
+            mark_css_text("<p markup-shorthands='css no'>a<p>b</p>c</p>d"),
+            "<p>a<p>b</p>c</p>[d]"
+        );
+        assert_eq!(
+            mark_css_text(
+                "<div markup-shorthands='css no'>a<div markup-shorthands='css yes'>b</div></div>"
+            ),
+            "a[b]"
+        );
+    }
+}
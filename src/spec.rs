@@ -3,25 +3,44 @@ use kuchiki::NodeRef;
 use std::collections::{BTreeMap, HashMap};
 use std::fs;
 
+use crate::anchors::{self, Anchor};
+use crate::assertions::{self, Assertion};
 use crate::boilerplate;
 use crate::config::SOURCE_FILE_EXTENSIONS;
+use crate::datablock;
+use crate::highlight;
 use crate::html;
+use crate::idl::{self, Definition};
+use crate::issues_examples;
 use crate::line::Line;
+use crate::link;
 use crate::metadata::metadata::{self, Metadata};
+use crate::railroad;
+use crate::rfc2119;
+use crate::shorthand;
 use crate::util::reader;
 
 #[derive(Debug, Default)]
 pub struct Spec<'a> {
-    infile: &'a str,
-    lines: Vec<Line>,
+    pub infile: &'a str,
+    pub lines: Vec<Line>,
     pub md: Metadata,
     pub md_cli: Metadata,
-    pub macros: HashMap<&'static str, String>,
+    pub macros: HashMap<String, String>,
     html: String,
     pub document: Option<NodeRef>,
     pub head: Option<NodeRef>,
     pub body: Option<NodeRef>,
     pub extra_styles: BTreeMap<&'static str, &'static str>,
+    pub write_assertions: bool,
+    pub assertions: Vec<Assertion>,
+    pub idl: Vec<Definition>,
+    pub export_idl_json: bool,
+    pub anchors: Vec<Anchor>,
+    // the anchors of other specs that links were resolved to
+    pub external_refs: Vec<Anchor>,
+    // the output of the blocks that is inserted as it is (e.g. included code)
+    pub verbatim_blocks: Vec<String>,
 }
 
 impl<'a> Spec<'a> {
@@ -52,6 +71,7 @@ impl<'a> Spec<'a> {
                     lines.push(Line {
                         index: 1 + (index as u32),
                         text: text,
+                        file: None,
                     });
                 }
             }
@@ -65,23 +85,39 @@ impl<'a> Spec<'a> {
     }
 
     fn assemble_document(&mut self) {
+        let lines = std::mem::take(&mut self.lines);
+        self.lines = datablock::include::transform_includes(lines, self.infile);
+
         let (mut md, lines) = metadata::parse_metadata(&self.lines);
         self.lines = lines;
 
         md.join(self.md_cli.clone());
+        // metadata values can use the macros of other metadata
+        md.fill_macros(self);
+        md.replace_macros(&self.macros);
         md.compute_implicit_metadata();
         md.fill_macros(self);
+        md.unescape_macros();
         md.validate();
         self.md = md;
 
+        let (anchors, lines) = anchors::extract_anchors(std::mem::take(&mut self.lines));
+        self.anchors = anchors;
+        self.lines = lines;
+
+        self.idl = idl::parse_idl_blocks(&self.lines);
+        datablock::transform_data_blocks(self);
+
         self.html = self
             .lines
             .iter()
             .map(|l| l.text.clone())
             .collect::<Vec<String>>()
             .join("\n");
+        self.html = shorthand::process_shorthands(&self.html, &self.md.shorthands());
         boilerplate::add_header_footer(&mut self.html);
         self.html = html::helper::replace_macros(&self.html, &self.macros);
+        self.html = datablock::restore_verbatim_blocks(&self.html, &self.verbatim_blocks);
 
         self.document = Some(kuchiki::parse_html().one(self.html.clone()));
         if let Ok(head) = self.document.as_ref().unwrap().select_first("head") {
@@ -93,7 +129,29 @@ impl<'a> Spec<'a> {
     }
 
     fn process_document(&mut self) {
+        boilerplate::add_conformance(self);
+        issues_examples::process_paragraph_prefixes(self);
+        issues_examples::add_note_headings(self);
+        issues_examples::process_examples(self);
+        issues_examples::process_issues(self);
+        highlight::highlight_code_blocks(self);
+        highlight::lines::process_line_numbers(self);
+        railroad::add_railroad_style(self);
+        rfc2119::mark_rfc2119_keywords(self);
+        if self.write_assertions {
+            assertions::collect_assertions(self);
+        }
+        link::add_dfn_ids(self);
         boilerplate::add_canonical_url(self);
+        boilerplate::add_spec_metadata(self);
+        boilerplate::add_logo(self);
+        boilerplate::add_warning(self);
+        boilerplate::add_copyright(self);
+        boilerplate::add_property_index(self);
+        boilerplate::add_element_index(self);
+        boilerplate::add_issues_index(self);
+        link::process_autolinks(self);
+        boilerplate::add_index_section(self);
         boilerplate::add_bikeshed_boilerplate(self);
     }
 
@@ -101,7 +159,13 @@ impl<'a> Spec<'a> {
         if let Some(document) = &self.document {
             let outfile = self.handle_outfile(outfile);
             let rendered = document.to_string();
-            fs::write(outfile, rendered).expect("unable to write file");
+            fs::write(&outfile, rendered).expect("unable to write file");
+            if self.write_assertions {
+                assertions::write_manifest(self, &outfile);
+            }
+            if self.export_idl_json {
+                idl::write_json(self, &outfile);
+            }
         }
     }
 
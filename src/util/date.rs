@@ -47,4 +47,9 @@ impl Date {
     pub fn day(&self) -> u32 {
         self.date.day()
     }
+
+    #[inline]
+    pub fn year(&self) -> i32 {
+        self.date.year()
+    }
 }
@@ -6,10 +6,26 @@ macro_rules! die {
     });
 
     ($($x:expr),+; $line:expr) => ({
-        if $line.is_some() {
-            eprint!("[Line {}] ", $line.unwrap());
+        if let Some(location) = $line {
+            eprint!("[{}] ", location);
         }
         eprintln!($($x),+);
         std::process::exit(1);
     });
 }
+
+#[macro_export]
+macro_rules! warn {
+    ($($x:expr),+) => ({
+        eprint!("Warning: ");
+        eprintln!($($x),+);
+    });
+
+    ($($x:expr),+; $line:expr) => ({
+        if let Some(location) = $line {
+            eprint!("[{}] ", location);
+        }
+        eprint!("Warning: ");
+        eprintln!($($x),+);
+    });
+}
@@ -1,5 +1,10 @@
 use regex::{Captures, Regex};
 
+lazy_static! {
+    // key-val pair reg, shared by metadata and definition blocks
+    pub static ref PAIR_REG: Regex = Regex::new(r"([^:]+):\s*(.*)").unwrap();
+}
+
 pub fn replace_all(reg: &Regex, haystack: &str, replacer: impl Fn(&Captures) -> String) -> String {
     let mut new = String::with_capacity(haystack.len());
     let mut last_match = 0;